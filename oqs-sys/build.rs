@@ -1,16 +1,20 @@
+use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
-fn generate_bindings(includedir: &Path, headerfile: &str, allow_filter: &str, block_filter: &str) {
+fn generate_bindings(
+    includedir: &Path,
+    name: &str,
+    headers: &[PathBuf],
+    allow_filter: &str,
+    block_filter: &str,
+) {
     let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
-    bindgen::Builder::default()
+    headers
+        .iter()
+        .fold(bindgen::Builder::default(), |builder, header| {
+            builder.header(header.to_str().unwrap())
+        })
         .clang_arg(format!("-I{}", includedir.display()))
-        .header(
-            includedir
-                .join("oqs")
-                .join(format!("{headerfile}.h"))
-                .to_str()
-                .unwrap(),
-        )
         // Options
         .default_enum_style(bindgen::EnumVariation::Rust {
             non_exhaustive: false,
@@ -34,10 +38,72 @@ fn generate_bindings(includedir: &Path, headerfile: &str, allow_filter: &str, bl
         .generate()
         // Unwrap the Result and panic on failure.
         .expect("Unable to generate bindings")
-        .write_to_file(out_path.join(format!("{headerfile}_bindings.rs")))
+        .write_to_file(out_path.join(format!("{name}_bindings.rs")))
         .expect("Couldn't write bindings!");
 }
 
+/// Find the headers that declare the functions of the symmetric primitive `name`, such as `sha3`
+///
+/// Older releases of liboqs installed `oqs/{name}.h`. Recent ones only install
+/// `oqs/{name}_ops.h` with the types and callbacks, and keep the functions in
+/// `liboqs/src/common/{dir}/{name}.h`, which we only use when we built liboqs ourselves.
+/// Returns `None` if the functions are not declared anywhere we can use.
+fn symmetric_headers(
+    includedir: &Path,
+    name: &str,
+    dir: &str,
+    vendored: bool,
+) -> Option<Vec<PathBuf>> {
+    let installed = includedir.join("oqs").join(format!("{name}.h"));
+    let source = Path::new("liboqs/src/common")
+        .join(dir)
+        .join(format!("{name}.h"));
+    let declarations = if installed.exists() {
+        installed
+    } else if vendored && source.exists() {
+        source.canonicalize().unwrap()
+    } else {
+        return None;
+    };
+    let ops = includedir.join("oqs").join(format!("{name}_ops.h"));
+    Some(
+        ops.exists()
+            .then_some(ops)
+            .into_iter()
+            .chain([declarations])
+            .collect(),
+    )
+}
+
+/// Record how liboqs was built, for `oqs_sys::build_info`
+fn generate_build_info(includedir: &Path, vendored: bool) {
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let config = std::fs::read_to_string(includedir.join("oqs").join("oqsconfig.h"))
+        .expect("Couldn't read oqsconfig.h");
+
+    let mut info = String::new();
+    writeln!(info, "/// Was liboqs built from the bundled sources").unwrap();
+    writeln!(info, "pub const VENDORED: bool = {vendored};").unwrap();
+    writeln!(info, "/// The macros defined in `oqsconfig.h`").unwrap();
+    writeln!(info, "pub const OQSCONFIG: &[(&str, &str)] = &[").unwrap();
+    for line in config.lines() {
+        let Some(define) = line.trim().strip_prefix("#define ") else {
+            continue;
+        };
+        let (name, value) = define
+            .split_once(char::is_whitespace)
+            .unwrap_or((define, ""));
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        writeln!(info, "    ({name:?}, {value:?}),").unwrap();
+    }
+    writeln!(info, "];").unwrap();
+    std::fs::write(out_path.join("build_info.rs"), info).expect("Couldn't write build info!");
+}
+
 fn build_from_source() -> PathBuf {
     let mut config = cmake::Config::new("liboqs");
     config.profile("Release");
@@ -83,6 +149,21 @@ fn build_from_source() -> PathBuf {
     algorithm_feature!("SIG", "sphincs");
     algorithm_feature!("SIG", "uov");
 
+    // stateful signature schemes
+    algorithm_feature!("SIG_STFL", "lms");
+    algorithm_feature!("SIG_STFL", "xmss");
+    // Key generation and signing are hazardous for stateful schemes,
+    // so liboqs only builds verification unless asked otherwise.
+    let value = if cfg!(feature = "stateful_key_sig_gen") {
+        "Yes"
+    } else {
+        "No"
+    };
+    config.define(
+        "OQS_HAZARDOUS_EXPERIMENTAL_ENABLE_SIG_STFL_KEY_SIG_GEN",
+        value,
+    );
+
     if cfg!(windows) {
         // Select the latest available Windows SDK
         // SDK version 10.0.17763.0 seems broken
@@ -165,9 +246,10 @@ fn includedir_from_source() -> PathBuf {
     outdir.join("include")
 }
 
-fn probe_includedir() -> PathBuf {
+/// Find the liboqs headers, and whether we built liboqs ourselves
+fn probe_includedir() -> (PathBuf, bool) {
     if cfg!(feature = "vendored") {
-        return includedir_from_source();
+        return (includedir_from_source(), true);
     }
 
     println!("cargo:rerun-if-env-changed=LIBOQS_NO_VENDOR");
@@ -187,13 +269,13 @@ fn probe_includedir() -> PathBuf {
         .probe("liboqs");
 
     match config {
-        Ok(lib) => lib.include_paths.first().cloned().unwrap(),
+        Ok(lib) => (lib.include_paths.first().cloned().unwrap(), false),
         _ => {
             if force_no_vendor {
                 panic!("The env variable LIBOQS_NO_VENDOR has been set but a suitable system liboqs could not be found.");
             }
 
-            includedir_from_source()
+            (includedir_from_source(), true)
         }
     }
 }
@@ -202,15 +284,42 @@ fn main() {
     // Check if clang is available before compiling anything.
     bindgen::clang_version();
 
-    let includedir = probe_includedir();
-    let gen_bindings = |file, allow_filter, block_filter| {
-        generate_bindings(&includedir, file, allow_filter, block_filter)
+    let (includedir, vendored) = probe_includedir();
+    generate_build_info(&includedir, vendored);
+    let gen_bindings = |file: &str, allow_filter, block_filter| {
+        let header = includedir.join("oqs").join(format!("{file}.h"));
+        generate_bindings(&includedir, file, &[header], allow_filter, block_filter)
+    };
+    // The bindings of the symmetric primitives are only generated if liboqs declares them,
+    // `cfg(oqs_{name})` enables them here and `DEP_OQS_{NAME}` tells the `oqs` crate.
+    let gen_symmetric_bindings = |file: &str, dir, allow_filter, block_filter| {
+        println!("cargo:rustc-check-cfg=cfg(oqs_{file})");
+        match symmetric_headers(&includedir, file, dir, vendored) {
+            Some(headers) => {
+                generate_bindings(&includedir, file, &headers, allow_filter, block_filter);
+                println!("cargo:rustc-cfg=oqs_{file}");
+                println!("cargo:{file}=1");
+            }
+            None => println!(
+                "cargo:warning=liboqs doesn't install the declarations of oqs/{file}.h, \
+                 its bindings are not available"
+            ),
+        }
     };
 
     gen_bindings("common", "OQS_.*", "");
     gen_bindings("rand", "OQS_(randombytes|RAND).*", "");
     gen_bindings("kem", "OQS_KEM.*", "");
     gen_bindings("sig", "OQS_SIG.*", "OQS_SIG_STFL.*");
+    gen_bindings(
+        "sig_stfl",
+        "OQS_SIG_STFL.*|secure_store_sk|lock_key|unlock_key",
+        "",
+    );
+    gen_symmetric_bindings("aes", "aes", "OQS_AES.*", "");
+    gen_symmetric_bindings("sha2", "sha2", "OQS_SHA2.*", "");
+    gen_symmetric_bindings("sha3", "sha3", "OQS_SHA3.*", "OQS_SHA3_.*_x4.*");
+    gen_symmetric_bindings("sha3x4", "sha3", "OQS_SHA3_.*_x4.*", "");
 
     // https://docs.rs/build-deps/0.1.4/build_deps/fn.rerun_if_changed_paths.html
     build_deps::rerun_if_changed_paths("liboqs/src/**/*").unwrap();
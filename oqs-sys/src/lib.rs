@@ -1,6 +1,11 @@
 //! Bindings to liboqs
 #![no_std]
 
+/// How liboqs was built
+pub mod build_info {
+    include!(concat!(env!("OUT_DIR"), "/build_info.rs"));
+}
+
 #[allow(clippy::all)]
 #[allow(non_upper_case_globals)]
 #[allow(non_camel_case_types)]
@@ -35,3 +40,50 @@ pub mod sig {
     pub use super::common::OQS_STATUS;
     include!(concat!(env!("OUT_DIR"), "/sig_bindings.rs"));
 }
+
+#[allow(clippy::all)]
+#[allow(non_upper_case_globals)]
+#[allow(non_camel_case_types)]
+#[allow(non_snake_case)]
+pub mod sig_stfl {
+    pub use super::common::OQS_STATUS;
+    include!(concat!(env!("OUT_DIR"), "/sig_stfl_bindings.rs"));
+}
+
+// The symmetric primitives are only bound if build.rs found the headers declaring them,
+// see `symmetric_headers` there.
+#[cfg(oqs_aes)]
+#[allow(clippy::all)]
+#[allow(non_upper_case_globals)]
+#[allow(non_camel_case_types)]
+#[allow(non_snake_case)]
+pub mod aes {
+    include!(concat!(env!("OUT_DIR"), "/aes_bindings.rs"));
+}
+
+#[cfg(oqs_sha2)]
+#[allow(clippy::all)]
+#[allow(non_upper_case_globals)]
+#[allow(non_camel_case_types)]
+#[allow(non_snake_case)]
+pub mod sha2 {
+    include!(concat!(env!("OUT_DIR"), "/sha2_bindings.rs"));
+}
+
+#[cfg(oqs_sha3)]
+#[allow(clippy::all)]
+#[allow(non_upper_case_globals)]
+#[allow(non_camel_case_types)]
+#[allow(non_snake_case)]
+pub mod sha3 {
+    include!(concat!(env!("OUT_DIR"), "/sha3_bindings.rs"));
+}
+
+#[cfg(oqs_sha3x4)]
+#[allow(clippy::all)]
+#[allow(non_upper_case_globals)]
+#[allow(non_camel_case_types)]
+#[allow(non_snake_case)]
+pub mod sha3x4 {
+    include!(concat!(env!("OUT_DIR"), "/sha3x4_bindings.rs"));
+}
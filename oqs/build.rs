@@ -4,4 +4,15 @@ fn main() {
         // through oqs-sys/kems in Cargo.toml
         println!("cargo:rustc-cfg=feature=\"bike\"");
     }
+
+    // oqs-sys only has bindings for the symmetric primitives
+    // if the linked liboqs declares them, see its build.rs
+    let bound = |name: &str| std::env::var_os(format!("DEP_OQS_{name}")).is_some();
+    println!("cargo:rustc-check-cfg=cfg(oqs_aes, oqs_hash)");
+    if bound("AES") {
+        println!("cargo:rustc-cfg=oqs_aes");
+    }
+    if bound("SHA2") && bound("SHA3") {
+        println!("cargo:rustc-cfg=oqs_hash");
+    }
 }
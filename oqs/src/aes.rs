@@ -0,0 +1,354 @@
+//! AES-128 and AES-256
+//!
+//! liboqs ships AES for FrodoKEM-AES and its other AES-based schemes. It
+//! runs on OpenSSL if liboqs was built with it, and otherwise on AES-NI or
+//! the ARMv8 cryptography extensions if the CPU has them, with a
+//! constant-time software fallback.
+//!
+//! [`Aes128`] and [`Aes256`] hold an expanded key, which liboqs wipes when
+//! they are dropped. They encrypt in ECB mode and generate CTR keystreams;
+//! liboqs has no decryption.
+//!
+//! ```
+//! use oqs::aes::Aes256;
+//!
+//! oqs::init();
+//! let aes = Aes256::new(&[0x42; 32]);
+//! let mut ciphertext = [0u8; 32];
+//! aes.encrypt_ecb(&[0u8; 32], &mut ciphertext)?;
+//! assert_eq!(ciphertext[..16], ciphertext[16..]);
+//!
+//! let mut message = *b"attack at dawn";
+//! let mut keystream = [0u8; 14];
+//! aes.ctr_keystream(&[0u8; 12], &mut keystream)?;
+//! message.iter_mut().zip(keystream).for_each(|(m, k)| *m ^= k);
+//! # Ok::<(), oqs::Error>(())
+//! ```
+use core::fmt;
+use core::ptr::{null_mut, NonNull};
+
+use crate::ffi::aes as ffi;
+use crate::*;
+
+/// Length of an AES block in bytes
+pub const BLOCK_LENGTH: usize = 16;
+
+/// Number of counter blocks [`Aes128::ctr_keystream`] encrypts at once
+const CTR_BATCH: usize = 16;
+
+/// Check the lengths of an ECB encryption
+fn check_ecb(algorithm: &'static str, input: &[u8], output: &[u8]) -> Result<()> {
+    let blocks = input.len().next_multiple_of(BLOCK_LENGTH);
+    check_length(Operation::Encrypt, algorithm, blocks, input.len())?;
+    check_length(Operation::Encrypt, algorithm, input.len(), output.len())
+}
+
+/// Check the length of a CTR IV
+///
+/// A 12-byte IV is followed by a 32-bit big-endian block counter that starts
+/// at zero. A 16-byte IV includes the initial value of that counter.
+fn check_iv(algorithm: &'static str, iv: &[u8]) -> Result<()> {
+    match iv.len() {
+        12 | 16 => Ok(()),
+        actual => Err(Error::WrongLength {
+            operation: Operation::Encrypt,
+            algorithm,
+            expected: 12,
+            actual,
+        }),
+    }
+}
+
+/// Take ownership of a key schedule allocated by liboqs
+fn schedule(schedule: *mut libc::c_void) -> NonNull<libc::c_void> {
+    NonNull::new(schedule).expect("liboqs did not allocate an AES key schedule")
+}
+
+/// AES-128 key schedule
+pub struct Aes128 {
+    schedule: NonNull<libc::c_void>,
+}
+
+impl Aes128 {
+    /// Length of the key in bytes
+    pub const KEY_LENGTH: usize = 16;
+
+    /// Expand `key`
+    pub fn new(key: &[u8; 16]) -> Self {
+        let mut ptr = null_mut();
+        unsafe { ffi::OQS_AES128_ECB_load_schedule(key.as_ptr(), &mut ptr) };
+        Self {
+            schedule: schedule(ptr),
+        }
+    }
+
+    /// Encrypt `input` in ECB mode
+    ///
+    /// `input` must be a whole number of blocks, and `output` must have the
+    /// same length.
+    pub fn encrypt_ecb(&self, input: &[u8], output: &mut [u8]) -> Result<()> {
+        check_ecb("AES-128", input, output)?;
+        unsafe {
+            ffi::OQS_AES128_ECB_enc_sch(
+                input.as_ptr(),
+                input.len(),
+                self.schedule.as_ptr(),
+                output.as_mut_ptr(),
+            )
+        };
+        Ok(())
+    }
+
+    /// Fill `output` with the CTR keystream for `iv`
+    ///
+    /// `iv` is 12 bytes followed by a 32-bit big-endian block counter
+    /// starting at zero, or 16 bytes including the initial counter.
+    /// XOR the keystream into a message to encrypt or decrypt it.
+    pub fn ctr_keystream(&self, iv: &[u8], output: &mut [u8]) -> Result<()> {
+        check_iv("AES-128", iv)?;
+        // liboqs only has AES-128 in ECB mode, so encrypt the counter blocks
+        let mut counter = iv
+            .get(12..)
+            .map_or(0, |c| u32::from_be_bytes(c.try_into().unwrap()));
+        let mut counters = [0u8; CTR_BATCH * BLOCK_LENGTH];
+        let mut keystream = [0u8; CTR_BATCH * BLOCK_LENGTH];
+        for chunk in output.chunks_mut(keystream.len()) {
+            let len = chunk.len().div_ceil(BLOCK_LENGTH) * BLOCK_LENGTH;
+            for block in counters[..len].chunks_exact_mut(BLOCK_LENGTH) {
+                block[..12].copy_from_slice(&iv[..12]);
+                block[12..].copy_from_slice(&counter.to_be_bytes());
+                counter = counter.wrapping_add(1);
+            }
+            self.encrypt_ecb(&counters[..len], &mut keystream[..len])?;
+            chunk.copy_from_slice(&keystream[..chunk.len()]);
+        }
+        unsafe {
+            crate::ffi::common::OQS_MEM_cleanse(
+                keystream.as_mut_ptr() as *mut libc::c_void,
+                keystream.len(),
+            )
+        };
+        Ok(())
+    }
+}
+
+impl Drop for Aes128 {
+    fn drop(&mut self) {
+        unsafe { ffi::OQS_AES128_free_schedule(self.schedule.as_ptr()) };
+    }
+}
+
+impl fmt::Debug for Aes128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aes128").finish_non_exhaustive()
+    }
+}
+
+// Not `Sync`: with OpenSSL, the schedule is an `EVP_CIPHER_CTX` that liboqs
+// modifies on every call, so it must not be used from two threads at once.
+unsafe impl Send for Aes128 {}
+
+/// AES-256 key schedule
+pub struct Aes256 {
+    schedule: NonNull<libc::c_void>,
+    /// liboqs keeps a separate schedule for CTR mode
+    ctr_schedule: NonNull<libc::c_void>,
+}
+
+impl Aes256 {
+    /// Length of the key in bytes
+    pub const KEY_LENGTH: usize = 32;
+
+    /// Expand `key`
+    pub fn new(key: &[u8; 32]) -> Self {
+        let (mut ptr, mut ctr_ptr) = (null_mut(), null_mut());
+        unsafe {
+            ffi::OQS_AES256_ECB_load_schedule(key.as_ptr(), &mut ptr);
+            ffi::OQS_AES256_CTR_inc_init(key.as_ptr(), &mut ctr_ptr);
+        }
+        Self {
+            schedule: schedule(ptr),
+            ctr_schedule: schedule(ctr_ptr),
+        }
+    }
+
+    /// Encrypt `input` in ECB mode
+    ///
+    /// `input` must be a whole number of blocks, and `output` must have the
+    /// same length.
+    pub fn encrypt_ecb(&self, input: &[u8], output: &mut [u8]) -> Result<()> {
+        check_ecb("AES-256", input, output)?;
+        unsafe {
+            ffi::OQS_AES256_ECB_enc_sch(
+                input.as_ptr(),
+                input.len(),
+                self.schedule.as_ptr(),
+                output.as_mut_ptr(),
+            )
+        };
+        Ok(())
+    }
+
+    /// Fill `output` with the CTR keystream for `iv`
+    ///
+    /// `iv` is 12 bytes followed by a 32-bit big-endian block counter
+    /// starting at zero, or 16 bytes including the initial counter.
+    /// XOR the keystream into a message to encrypt or decrypt it.
+    pub fn ctr_keystream(&self, iv: &[u8], output: &mut [u8]) -> Result<()> {
+        check_iv("AES-256", iv)?;
+        unsafe {
+            ffi::OQS_AES256_CTR_inc_stream_iv(
+                iv.as_ptr(),
+                iv.len(),
+                self.ctr_schedule.as_ptr(),
+                output.as_mut_ptr(),
+                output.len(),
+            )
+        };
+        Ok(())
+    }
+}
+
+impl Drop for Aes256 {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::OQS_AES256_free_schedule(self.schedule.as_ptr());
+            ffi::OQS_AES256_free_schedule(self.ctr_schedule.as_ptr());
+        }
+    }
+}
+
+impl fmt::Debug for Aes256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aes256").finish_non_exhaustive()
+    }
+}
+
+// Not `Sync`, see `Aes128`
+unsafe impl Send for Aes256 {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+        a.iter().zip(b).map(|(a, b)| a ^ b).collect()
+    }
+
+    /// The example vectors of FIPS 197, appendix C
+    #[test]
+    fn test_ecb() -> Result<()> {
+        crate::init();
+        let key: Vec<u8> = (0..32).collect();
+        let plaintext = unhex("00112233445566778899aabbccddeeff");
+        let mut ciphertext = [0u8; 16];
+        Aes128::new(key[..16].try_into().unwrap()).encrypt_ecb(&plaintext, &mut ciphertext)?;
+        assert_eq!(ciphertext[..], unhex("69c4e0d86a7b0430d8cdb78070b4c55a"));
+        Aes256::new(key[..].try_into().unwrap()).encrypt_ecb(&plaintext, &mut ciphertext)?;
+        assert_eq!(ciphertext[..], unhex("8ea2b7ca516745bfeafc49904b496089"));
+        Ok(())
+    }
+
+    /// The CTR vectors of NIST SP 800-38A, appendix F.5
+    #[test]
+    fn test_ctr() -> Result<()> {
+        crate::init();
+        let iv = unhex("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let plaintext = unhex(concat!(
+            "6bc1bee22e409f96e93d7e117393172a",
+            "ae2d8a571e03ac9c9eb76fac45af8e51"
+        ));
+        let mut keystream = [0u8; 32];
+
+        let key = unhex("2b7e151628aed2a6abf7158809cf4f3c");
+        let ciphertext = unhex(concat!(
+            "874d6191b620e3261bef6864990db6ce",
+            "9806f66b7970fdff8617187bb9fffdff"
+        ));
+        Aes128::new(key[..].try_into().unwrap()).ctr_keystream(&iv, &mut keystream)?;
+        assert_eq!(keystream[..], xor(&plaintext, &ciphertext));
+
+        let key = unhex("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4");
+        let ciphertext = unhex(concat!(
+            "601ec313775789a5b7a7f504bbf3d228",
+            "f443e3ca4d62b59aca84e990cacaf5c5"
+        ));
+        Aes256::new(key[..].try_into().unwrap()).ctr_keystream(&iv, &mut keystream)?;
+        assert_eq!(keystream[..], xor(&plaintext, &ciphertext));
+        Ok(())
+    }
+
+    macro_rules! test_ctr_counter {
+        ($name:ident, $aes:ident) => {
+            #[test]
+            fn $name() -> Result<()> {
+                crate::init();
+                let aes = $aes::new(&[7; $aes::KEY_LENGTH]);
+                let nonce = [9u8; 12];
+                let mut keystream = vec![0u8; 1000];
+                aes.ctr_keystream(&nonce, &mut keystream)?;
+
+                // a 12-byte IV starts counting at zero
+                let mut counters = Vec::new();
+                for counter in 0..63u32 {
+                    counters.extend_from_slice(&nonce);
+                    counters.extend_from_slice(&counter.to_be_bytes());
+                }
+                let mut expected = vec![0u8; counters.len()];
+                aes.encrypt_ecb(&counters, &mut expected)?;
+                assert_eq!(keystream, expected[..1000]);
+
+                // a 16-byte IV continues from its counter
+                let mut partial = vec![0u8; 37];
+                aes.ctr_keystream(&counters[16 * 5..16 * 6], &mut partial)?;
+                assert_eq!(partial, keystream[16 * 5..16 * 5 + 37]);
+                Ok(())
+            }
+        };
+    }
+
+    test_ctr_counter!(test_ctr_counter_aes128, Aes128);
+    test_ctr_counter!(test_ctr_counter_aes256, Aes256);
+
+    #[test]
+    fn test_invalid_lengths() {
+        crate::init();
+        let aes = Aes128::new(&[0; 16]);
+        let mut output = [0u8; 32];
+        assert!(matches!(
+            aes.encrypt_ecb(&[0; 15], &mut output[..15]),
+            Err(Error::WrongLength {
+                operation: Operation::Encrypt,
+                algorithm: "AES-128",
+                expected: 16,
+                actual: 15,
+            })
+        ));
+        assert!(matches!(
+            aes.encrypt_ecb(&[0; 16], &mut output),
+            Err(Error::WrongLength {
+                expected: 16,
+                actual: 32,
+                ..
+            })
+        ));
+        let aes = Aes256::new(&[0; 32]);
+        assert!(matches!(
+            aes.ctr_keystream(&[0; 8], &mut output),
+            Err(Error::WrongLength {
+                algorithm: "AES-256",
+                actual: 8,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+//! How the linked liboqs was built
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use cstr_core::CStr;
+#[cfg(feature = "std")]
+use std::ffi::CStr;
+
+use crate::ffi::build_info::{OQSCONFIG, VENDORED};
+use crate::{kem, sig, sig_stfl};
+
+/// The `oqsconfig.h` macros that enable each KEM family
+const KEM_FAMILIES: [(kem::Family, &str); 7] = [
+    (kem::Family::Bike, "OQS_ENABLE_KEM_BIKE"),
+    (
+        kem::Family::ClassicMcEliece,
+        "OQS_ENABLE_KEM_CLASSIC_MCELIECE",
+    ),
+    (kem::Family::FrodoKem, "OQS_ENABLE_KEM_FRODOKEM"),
+    (kem::Family::Hqc, "OQS_ENABLE_KEM_HQC"),
+    (kem::Family::Kyber, "OQS_ENABLE_KEM_KYBER"),
+    (kem::Family::MlKem, "OQS_ENABLE_KEM_ML_KEM"),
+    (kem::Family::NtruPrime, "OQS_ENABLE_KEM_NTRUPRIME"),
+];
+
+/// The `oqsconfig.h` macros that enable each signature family
+const SIG_FAMILIES: [(sig::Family, &str); 7] = [
+    (sig::Family::Cross, "OQS_ENABLE_SIG_CROSS"),
+    (sig::Family::Dilithium, "OQS_ENABLE_SIG_DILITHIUM"),
+    (sig::Family::Falcon, "OQS_ENABLE_SIG_FALCON"),
+    (sig::Family::Mayo, "OQS_ENABLE_SIG_MAYO"),
+    (sig::Family::MlDsa, "OQS_ENABLE_SIG_ML_DSA"),
+    (sig::Family::Sphincs, "OQS_ENABLE_SIG_SPHINCS"),
+    (sig::Family::Uov, "OQS_ENABLE_SIG_UOV"),
+];
+
+/// The `oqsconfig.h` macros that enable each stateful signature family
+const STATEFUL_SIG_FAMILIES: [(sig_stfl::Family, &str); 2] = [
+    (sig_stfl::Family::Lms, "OQS_ENABLE_SIG_STFL_LMS"),
+    (sig_stfl::Family::Xmss, "OQS_ENABLE_SIG_STFL_XMSS"),
+];
+
+/// How liboqs was built
+///
+/// This is read from `oqsconfig.h` when `oqs-sys` is built, so it describes
+/// the liboqs that was bundled with `oqs-sys` or found through pkg-config.
+/// See [`build_info`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BuildInfo {
+    /// Version of liboqs according to its headers
+    ///
+    /// [`version`](crate::version) returns the version of the linked library.
+    pub version: &'static str,
+    /// Platform that liboqs was built for
+    pub target: &'static str,
+    /// Was liboqs built from the sources bundled with `oqs-sys`,
+    /// rather than found through pkg-config
+    pub vendored: bool,
+    /// Does liboqs use OpenSSL for its symmetric cryptography and randomness
+    pub openssl: bool,
+    /// Was liboqs built for distribution (`OQS_DIST_BUILD`)
+    ///
+    /// Distribution builds detect at runtime which CPU extensions they can use.
+    /// Other builds, such as with the `non_portable` feature, use the
+    /// extensions in [`cpu_extensions`](BuildInfo::cpu_extensions) unconditionally.
+    pub dist_build: bool,
+    /// CPU extensions liboqs was compiled to use (`OQS_USE_*_INSTRUCTIONS`),
+    /// such as `AVX2` or `AES`
+    pub cpu_extensions: Vec<&'static str>,
+    /// Enabled KEM families
+    pub kem_families: Vec<kem::Family>,
+    /// Enabled signature families
+    pub sig_families: Vec<sig::Family>,
+    /// Enabled stateful signature families
+    pub stateful_sig_families: Vec<sig_stfl::Family>,
+    /// Are key generation and signing enabled for stateful signatures
+    pub stateful_key_sig_gen: bool,
+}
+
+/// Version of the linked liboqs, such as `"0.13.0"`
+///
+/// See [`build_info`] for how it was built.
+pub fn version() -> &'static str {
+    // SAFETY: liboqs returns a static, null-terminated string
+    let cstr = unsafe { CStr::from_ptr(crate::ffi::common::OQS_version()) };
+    cstr.to_str().expect("The liboqs version must be UTF-8")
+}
+
+/// Look up a macro in `oqsconfig.h`
+pub(crate) fn config(name: &str) -> Option<&'static str> {
+    OQSCONFIG
+        .iter()
+        .find(|(macro_name, _)| *macro_name == name)
+        .map(|(_, value)| *value)
+}
+
+fn enabled<T: Copy>(families: &[(T, &str)]) -> Vec<T> {
+    families
+        .iter()
+        .filter(|(_, name)| config(name).is_some())
+        .map(|(family, _)| *family)
+        .collect()
+}
+
+/// Report how liboqs was built
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: config("OQS_VERSION_TEXT").unwrap_or_default(),
+        target: config("OQS_COMPILE_BUILD_TARGET").unwrap_or_default(),
+        vendored: VENDORED,
+        openssl: config("OQS_USE_OPENSSL").is_some(),
+        dist_build: config("OQS_DIST_BUILD").is_some(),
+        cpu_extensions: OQSCONFIG
+            .iter()
+            .filter_map(|(name, _)| name.strip_prefix("OQS_USE_"))
+            .filter_map(|name| name.strip_suffix("_INSTRUCTIONS"))
+            .collect(),
+        kem_families: enabled(&KEM_FAMILIES),
+        sig_families: enabled(&SIG_FAMILIES),
+        stateful_sig_families: enabled(&STATEFUL_SIG_FAMILIES),
+        stateful_key_sig_gen: config("OQS_ALLOW_STFL_KEY_AND_SIG_GEN").is_some()
+            || config("OQS_HAZARDOUS_EXPERIMENTAL_ENABLE_SIG_STFL_KEY_SIG_GEN").is_some(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_version() {
+        crate::init();
+        assert_eq!(crate::version(), build_info().version);
+    }
+
+    #[test]
+    fn test_families() {
+        crate::init();
+        let info = build_info();
+        for algorithm in kem::Algorithm::enabled() {
+            assert!(info.kem_families.contains(&algorithm.info().family));
+        }
+        for algorithm in sig::Algorithm::enabled() {
+            assert!(info.sig_families.contains(&algorithm.info().family));
+        }
+        #[cfg(feature = "stateful_key_sig_gen")]
+        assert!(info.stateful_key_sig_gen);
+    }
+}
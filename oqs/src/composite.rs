@@ -0,0 +1,627 @@
+//! Composite signatures
+//!
+//! A composite signature pairs a post-quantum signature from liboqs with a
+//! classical Ed25519 or ECDSA signature. It only verifies if both
+//! components verify, so it stays secure as long as either is not broken.
+//!
+//! The ML-DSA combinations follow
+//! [draft-ietf-lamps-pq-composite-sigs](https://datatracker.ietf.org/doc/draft-ietf-lamps-pq-composite-sigs/):
+//! both components sign the message representative
+//!
+//! ```text
+//! M' = Prefix || Label || len(ctx) || ctx || PH(M)
+//! ```
+//!
+//! where `Prefix` is `CompositeAlgorithmSignatures2025`, `Label` is the
+//! [`Algorithm::label`] of the combination and `PH` is its pre-hash function.
+//! ML-DSA also receives the label as its context string.
+//!
+//! Two deviations from the draft:
+//! * liboqs can't derive ML-DSA keys from seeds, so the secret key contains the
+//!   expanded ML-DSA secret key instead of its seed.
+//! * The Falcon combinations are not part of the draft. They use the same construction,
+//!   but Falcon ignores the label, as it has no context strings.
+//!   As Falcon signatures vary in length, their length precedes them as a 16-bit
+//!   big-endian integer. These combinations have no OID.
+use alloc::vec::Vec;
+
+use const_oid::ObjectIdentifier;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::newtype_buffer;
+use crate::sig::{self, CtxStr, Message, Sig};
+use crate::*;
+
+newtype_buffer!(PublicKey, PublicKeyRef);
+newtype_buffer!(secret SecretKey, SecretKeyRef);
+newtype_buffer!(Signature, SignatureRef);
+
+/// `Prefix` of the message representative
+const PREFIX: &[u8] = b"CompositeAlgorithmSignatures2025";
+
+/// Classical component of a composite algorithm
+#[derive(Clone, Copy, Debug)]
+enum Trad {
+    Ed25519,
+    EcdsaP256,
+    EcdsaP384,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Hash {
+    Sha256,
+    Sha512,
+}
+
+impl Hash {
+    fn digest(self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Hash::Sha256 => Sha256::digest(message).to_vec(),
+            Hash::Sha512 => Sha512::digest(message).to_vec(),
+        }
+    }
+}
+
+macro_rules! implement_composites {
+    { $($composite: ident: $pq: ident, $trad: expr, $ph: ident, $label: literal, $oid: expr;)* } => (
+        /// Supported composite algorithms
+        ///
+        /// Optional support for `serde` if that feature is enabled.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        #[allow(missing_docs)]
+        pub enum Algorithm {
+            $($composite,)*
+        }
+
+        impl Algorithm {
+            /// Lists all composite algorithms
+            pub const ALL: &'static [Algorithm] = &[$(Algorithm::$composite,)*];
+
+            /// Returns the post-quantum component
+            pub fn pq_algorithm(self) -> sig::Algorithm {
+                match self {
+                    $(Algorithm::$composite => sig::Algorithm::$pq,)*
+                }
+            }
+
+            /// Returns the label that separates this combination from all others
+            pub fn label(self) -> &'static str {
+                match self {
+                    $(Algorithm::$composite => $label,)*
+                }
+            }
+
+            /// Returns the composite OID, if there is one
+            pub fn oid(self) -> Option<ObjectIdentifier> {
+                let oid: Option<&str> = match self {
+                    $(Algorithm::$composite => $oid,)*
+                };
+                oid.map(ObjectIdentifier::new_unwrap)
+            }
+
+            fn trad(self) -> Trad {
+                match self {
+                    $(Algorithm::$composite => $trad,)*
+                }
+            }
+
+            fn prehash(self) -> Hash {
+                match self {
+                    $(Algorithm::$composite => Hash::$ph,)*
+                }
+            }
+        }
+    )
+}
+
+implement_composites! {
+    MlDsa44Ed25519: MlDsa44, Trad::Ed25519, Sha512,
+        "COMPSIG-MLDSA44-Ed25519-SHA512", Some("1.3.6.1.5.5.7.6.39");
+    MlDsa44EcdsaP256: MlDsa44, Trad::EcdsaP256, Sha256,
+        "COMPSIG-MLDSA44-ECDSA-P256-SHA256", Some("1.3.6.1.5.5.7.6.40");
+    MlDsa65EcdsaP256: MlDsa65, Trad::EcdsaP256, Sha512,
+        "COMPSIG-MLDSA65-ECDSA-P256-SHA512", Some("1.3.6.1.5.5.7.6.45");
+    MlDsa65EcdsaP384: MlDsa65, Trad::EcdsaP384, Sha512,
+        "COMPSIG-MLDSA65-ECDSA-P384-SHA512", Some("1.3.6.1.5.5.7.6.46");
+    MlDsa65Ed25519: MlDsa65, Trad::Ed25519, Sha512,
+        "COMPSIG-MLDSA65-Ed25519-SHA512", Some("1.3.6.1.5.5.7.6.48");
+    MlDsa87EcdsaP384: MlDsa87, Trad::EcdsaP384, Sha512,
+        "COMPSIG-MLDSA87-ECDSA-P384-SHA512", Some("1.3.6.1.5.5.7.6.49");
+    Falcon512EcdsaP256: Falcon512, Trad::EcdsaP256, Sha256,
+        "COMPSIG-FALCON512-ECDSA-P256-SHA256", None;
+    Falcon512Ed25519: Falcon512, Trad::Ed25519, Sha512,
+        "COMPSIG-FALCON512-Ed25519-SHA512", None;
+    Falcon1024EcdsaP384: Falcon1024, Trad::EcdsaP384, Sha512,
+        "COMPSIG-FALCON1024-ECDSA-P384-SHA512", None;
+}
+
+impl Algorithm {
+    /// Look up the composite algorithm with the given OID
+    pub fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        Self::ALL.iter().copied().find(|alg| alg.oid() == Some(oid))
+    }
+
+    /// Does the post-quantum signature have a fixed length
+    fn fixed_length_pq_signature(self) -> bool {
+        !matches!(
+            self.pq_algorithm(),
+            sig::Algorithm::Falcon512 | sig::Algorithm::Falcon1024
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.label().fmt(f)
+    }
+}
+
+/// Draws randomness for the classical components from liboqs
+struct OqsRng;
+
+impl rand_core::RngCore for OqsRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        crate::rand::randombytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> core::result::Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl rand_core::CryptoRng for OqsRng {}
+
+/// The classical signature schemes
+///
+/// Public keys are raw Ed25519 keys or uncompressed SEC1 points.
+/// Secret keys are Ed25519 seeds or SEC1 `ECPrivateKey`s.
+mod trad {
+    use alloc::vec::Vec;
+
+    use ed25519_dalek::{Signer, Verifier};
+
+    use super::{OqsRng, Trad};
+    use crate::{Error, Operation, Result};
+
+    fn ed25519_bytes(operation: Operation, bytes: &[u8]) -> Result<&[u8; 32]> {
+        bytes.try_into().map_err(|_| Error::WrongLength {
+            operation,
+            algorithm: "Ed25519",
+            expected: 32,
+            actual: bytes.len(),
+        })
+    }
+
+    fn ed25519_secret(operation: Operation, sk: &[u8]) -> Result<ed25519_dalek::SigningKey> {
+        Ok(ed25519_dalek::SigningKey::from_bytes(ed25519_bytes(
+            operation, sk,
+        )?))
+    }
+
+    macro_rules! ecdsa {
+        ($name: ident, $curve: ident) => {
+            mod $name {
+                use alloc::vec::Vec;
+
+                use $curve::ecdsa::signature::{Signer, Verifier};
+                use $curve::ecdsa::{Signature, SigningKey, VerifyingKey};
+                use $curve::elliptic_curve::sec1::ToEncodedPoint;
+                use $curve::SecretKey;
+
+                use super::OqsRng;
+                use crate::{Error, Result};
+
+                pub(crate) fn keypair() -> Result<(Vec<u8>, Vec<u8>)> {
+                    let sk = SecretKey::random(&mut OqsRng);
+                    let pk = sk.public_key().to_encoded_point(false).as_bytes().to_vec();
+                    let sk = sk.to_sec1_der().map_err(|_| Error::Error)?;
+                    Ok((pk, sk.to_vec()))
+                }
+
+                pub(crate) fn sign(sk: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+                    let sk = SecretKey::from_sec1_der(sk).map_err(|_| Error::Error)?;
+                    let signature: Signature = SigningKey::from(sk).sign(message);
+                    Ok(signature.to_der().as_bytes().to_vec())
+                }
+
+                pub(crate) fn verify(pk: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+                    let pk = VerifyingKey::from_sec1_bytes(pk).map_err(|_| Error::Error)?;
+                    let signature = Signature::from_der(signature).map_err(|_| Error::Error)?;
+                    pk.verify(message, &signature).map_err(|_| Error::Error)
+                }
+            }
+        };
+    }
+
+    ecdsa!(ecdsa_p256, p256);
+    ecdsa!(ecdsa_p384, p384);
+
+    impl Trad {
+        /// Generate a keypair, returns the public and the secret key
+        pub(super) fn keypair(self) -> Result<(Vec<u8>, Vec<u8>)> {
+            match self {
+                Trad::Ed25519 => {
+                    let mut sk = alloc::vec![0; 32];
+                    crate::rand::randombytes(&mut sk);
+                    let signing_key = ed25519_secret(Operation::Keypair, &sk)?;
+                    let pk = signing_key.verifying_key().to_bytes().to_vec();
+                    Ok((pk, sk))
+                }
+                Trad::EcdsaP256 => ecdsa_p256::keypair(),
+                Trad::EcdsaP384 => ecdsa_p384::keypair(),
+            }
+        }
+
+        pub(super) fn sign(self, sk: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+            match self {
+                Trad::Ed25519 => {
+                    let sk = ed25519_secret(Operation::Sign, sk)?;
+                    Ok(sk.sign(message).to_bytes().to_vec())
+                }
+                Trad::EcdsaP256 => ecdsa_p256::sign(sk, message),
+                Trad::EcdsaP384 => ecdsa_p384::sign(sk, message),
+            }
+        }
+
+        pub(super) fn verify(self, pk: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+            match self {
+                Trad::Ed25519 => {
+                    let pk = ed25519_bytes(Operation::Verify, pk)?;
+                    let pk =
+                        ed25519_dalek::VerifyingKey::from_bytes(pk).map_err(|_| Error::Error)?;
+                    let signature = ed25519_dalek::Signature::from_slice(signature)
+                        .map_err(|_| Error::Error)?;
+                    pk.verify(message, &signature).map_err(|_| Error::Error)
+                }
+                Trad::EcdsaP256 => ecdsa_p256::verify(pk, message, signature),
+                Trad::EcdsaP384 => ecdsa_p384::verify(pk, message, signature),
+            }
+        }
+    }
+}
+
+/// Composite signature scheme
+///
+/// Public keys, secret keys and signatures are the concatenation of the post-quantum
+/// and the classical component, see the [module documentation](self).
+///
+/// # Example
+/// ```rust
+/// # if !cfg!(feature = "ml_dsa") { return; }
+/// use oqs::composite::{Algorithm, CompositeSig};
+/// oqs::init();
+/// let scheme = CompositeSig::new(Algorithm::MlDsa65Ed25519).unwrap();
+/// let (pk, sk) = scheme.keypair().unwrap();
+/// let signature = scheme.sign_with_ctx_str(b"message", b"context", &sk).unwrap();
+/// assert!(scheme
+///     .verify_with_ctx_str(b"message", &signature, b"context", &pk)
+///     .is_ok());
+/// ```
+pub struct CompositeSig {
+    algorithm: Algorithm,
+    sig: Sig,
+}
+
+impl core::convert::TryFrom<Algorithm> for CompositeSig {
+    type Error = crate::Error;
+    fn try_from(alg: Algorithm) -> Result<CompositeSig> {
+        CompositeSig::new(alg)
+    }
+}
+
+impl CompositeSig {
+    /// Construct a new composite algorithm
+    pub fn new(algorithm: Algorithm) -> Result<Self> {
+        let sig = Sig::new(algorithm.pq_algorithm())?;
+        Ok(Self { algorithm, sig })
+    }
+
+    /// Get the algorithm used by this `CompositeSig`
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Obtain a secret key from bytes
+    ///
+    /// Returns None if the secret key is too short to hold both components.
+    pub fn secret_key_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<SecretKeyRef<'a>> {
+        if buf.len() <= self.sig.length_secret_key() {
+            None
+        } else {
+            Some(SecretKeyRef::new(buf))
+        }
+    }
+
+    /// Obtain a public key from bytes
+    ///
+    /// Returns None if the public key is too short to hold both components.
+    pub fn public_key_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<PublicKeyRef<'a>> {
+        if buf.len() <= self.sig.length_public_key() {
+            None
+        } else {
+            Some(PublicKeyRef::new(buf))
+        }
+    }
+
+    /// Obtain a signature from bytes
+    ///
+    /// Returns None if the signature can't be split into its components.
+    pub fn signature_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<SignatureRef<'a>> {
+        self.split_signature(buf).ok()?;
+        Some(SignatureRef::new(buf))
+    }
+
+    /// Generate a new keypair
+    pub fn keypair(&self) -> Result<(PublicKey, SecretKey)> {
+        let (pq_pk, pq_sk) = self.sig.keypair()?;
+        let (trad_pk, mut trad_sk) = self.algorithm.trad().keypair()?;
+        let pk = PublicKey {
+            bytes: [pq_pk.as_ref(), &trad_pk].concat(),
+        };
+        let sk = SecretKey {
+            bytes: [pq_sk.as_ref(), &trad_sk].concat(),
+        };
+        crate::macros::cleanse(&mut trad_sk);
+        Ok((pk, sk))
+    }
+
+    /// Sign a message
+    pub fn sign<'a, S: Into<SecretKeyRef<'a>>>(
+        &self,
+        message: &Message,
+        sk: S,
+    ) -> Result<Signature> {
+        self.sign_with_ctx_str(message, &[], sk)
+    }
+
+    /// Sign a message with a context string of at most 255 bytes
+    pub fn sign_with_ctx_str<'a, S: Into<SecretKeyRef<'a>>>(
+        &self,
+        message: &Message,
+        ctx_str: &CtxStr,
+        sk: S,
+    ) -> Result<Signature> {
+        let sk = sk.into();
+        let (pq_sk, trad_sk) = self.split_secret_key(&sk)?;
+        let representative = self.message_representative(Operation::Sign, message, ctx_str)?;
+        let pq_signature = if self.sig.has_ctx_str_support() {
+            let label = self.algorithm.label().as_bytes();
+            self.sig.sign_with_ctx_str(&representative, label, pq_sk)?
+        } else {
+            self.sig.sign(&representative, pq_sk)?
+        };
+        let trad_signature = self.algorithm.trad().sign(trad_sk, &representative)?;
+        let mut bytes = Vec::new();
+        if !self.algorithm.fixed_length_pq_signature() {
+            let len = u16::try_from(pq_signature.len()).map_err(|_| Error::Error)?;
+            bytes.extend_from_slice(&len.to_be_bytes());
+        }
+        bytes.extend_from_slice(pq_signature.as_ref());
+        bytes.extend_from_slice(&trad_signature);
+        Ok(Signature { bytes })
+    }
+
+    /// Verify a message
+    pub fn verify<'a, 'b>(
+        &self,
+        message: &Message,
+        signature: impl Into<SignatureRef<'a>>,
+        pk: impl Into<PublicKeyRef<'b>>,
+    ) -> Result<()> {
+        self.verify_with_ctx_str(message, signature, &[], pk)
+    }
+
+    /// Verify a message with a context string
+    ///
+    /// Only succeeds if both components verify.
+    pub fn verify_with_ctx_str<'a, 'b>(
+        &self,
+        message: &Message,
+        signature: impl Into<SignatureRef<'a>>,
+        ctx_str: &CtxStr,
+        pk: impl Into<PublicKeyRef<'b>>,
+    ) -> Result<()> {
+        let signature = signature.into();
+        let pk = pk.into();
+        if pk.len() <= self.sig.length_public_key() {
+            let expected = self.sig.length_public_key() + 1;
+            return Err(self.wrong_length(Operation::Verify, expected, pk.len()));
+        }
+        let (pq_pk, trad_pk) = pk.split_at(self.sig.length_public_key());
+        let pq_pk = self
+            .sig
+            .public_key_from_bytes(pq_pk)
+            .expect("split at the post-quantum public key length");
+        let (pq_signature, trad_signature) = self.split_signature(&signature)?;
+        let pq_signature = self
+            .sig
+            .signature_from_bytes(pq_signature)
+            .expect("split_signature checks the length");
+        let representative = self.message_representative(Operation::Verify, message, ctx_str)?;
+        let verified = if self.sig.has_ctx_str_support() {
+            let label = self.algorithm.label().as_bytes();
+            self.sig
+                .verify_with_ctx_str(&representative, pq_signature, label, pq_pk)
+        } else {
+            self.sig.verify(&representative, pq_signature, pq_pk)
+        };
+        verified
+            .and_then(|()| {
+                self.algorithm
+                    .trad()
+                    .verify(trad_pk, &representative, trad_signature)
+            })
+            .map_err(|error| match error {
+                Error::Error | Error::VerificationFailed { .. } => Error::VerificationFailed {
+                    algorithm: self.algorithm.label(),
+                },
+                error => error,
+            })
+    }
+
+    /// Compute `M' = Prefix || Label || len(ctx) || ctx || PH(M)`
+    fn message_representative(
+        &self,
+        operation: Operation,
+        message: &Message,
+        ctx_str: &CtxStr,
+    ) -> Result<Vec<u8>> {
+        let ctx_len = u8::try_from(ctx_str.len())
+            .map_err(|_| self.wrong_length(operation, u8::MAX.into(), ctx_str.len()))?;
+        Ok([
+            PREFIX,
+            self.algorithm.label().as_bytes(),
+            &[ctx_len],
+            ctx_str,
+            &self.algorithm.prehash().digest(message),
+        ]
+        .concat())
+    }
+
+    fn split_secret_key<'a>(
+        &self,
+        sk: &SecretKeyRef<'a>,
+    ) -> Result<(sig::SecretKeyRef<'a>, &'a [u8])> {
+        let sk: &'a [u8] = sk.bytes;
+        if sk.len() <= self.sig.length_secret_key() {
+            let expected = self.sig.length_secret_key() + 1;
+            return Err(self.wrong_length(Operation::Sign, expected, sk.len()));
+        }
+        let (pq_sk, trad_sk) = sk.split_at(self.sig.length_secret_key());
+        let pq_sk = self
+            .sig
+            .secret_key_from_bytes(pq_sk)
+            .expect("split at the post-quantum secret key length");
+        Ok((pq_sk, trad_sk))
+    }
+
+    fn split_signature<'a>(&self, signature: &'a [u8]) -> Result<(&'a [u8], &'a [u8])> {
+        let (pq_len, signature) = if self.algorithm.fixed_length_pq_signature() {
+            (self.sig.length_signature(), signature)
+        } else {
+            let len = signature
+                .get(..2)
+                .ok_or_else(|| self.wrong_length(Operation::Verify, 2, signature.len()))?;
+            let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+            (len, &signature[2..])
+        };
+        if pq_len > self.sig.length_signature() {
+            let expected = self.sig.length_signature();
+            return Err(self.wrong_length(Operation::Verify, expected, pq_len));
+        }
+        if signature.len() <= pq_len {
+            return Err(self.wrong_length(Operation::Verify, pq_len + 1, signature.len()));
+        }
+        Ok(signature.split_at(pq_len))
+    }
+
+    /// Error for a key, signature or context string of the wrong length
+    fn wrong_length(&self, operation: Operation, expected: usize, actual: usize) -> Error {
+        Error::WrongLength {
+            operation,
+            algorithm: self.algorithm.label(),
+            expected,
+            actual,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn roundtrip(algorithm: Algorithm) -> Result<()> {
+        crate::init();
+        let scheme = CompositeSig::new(algorithm)?;
+        let (pk, sk) = scheme.keypair()?;
+        let message = b"composite message";
+
+        let signature = scheme.sign(message, &sk)?;
+        scheme.verify(message, &signature, &pk)?;
+        assert!(matches!(
+            scheme.verify(b"other message", &signature, &pk),
+            Err(Error::VerificationFailed { .. })
+        ));
+
+        let signature = scheme.sign_with_ctx_str(message, b"context", &sk)?;
+        scheme.verify_with_ctx_str(message, &signature, b"context", &pk)?;
+        assert!(scheme.verify(message, &signature, &pk).is_err());
+        assert!(scheme
+            .verify_with_ctx_str(message, &signature, b"other context", &pk)
+            .is_err());
+
+        // both components must verify
+        let other = scheme.sign_with_ctx_str(b"other message", b"context", &sk)?;
+        let header_len = if algorithm.fixed_length_pq_signature() {
+            0
+        } else {
+            2
+        };
+        let (pq, _) = scheme.split_signature(signature.as_ref())?;
+        let (_, trad) = scheme.split_signature(other.as_ref())?;
+        let mixed = [&signature.as_ref()[..header_len], pq, trad].concat();
+        let mixed = scheme.signature_from_bytes(&mixed).unwrap();
+        assert!(scheme
+            .verify_with_ctx_str(message, mixed, b"context", &pk)
+            .is_err());
+
+        assert!(matches!(
+            scheme.sign_with_ctx_str(message, &[0; 256], &sk),
+            Err(Error::WrongLength {
+                operation: Operation::Sign,
+                expected: 255,
+                actual: 256,
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ml_dsa")]
+    fn test_mldsa_ed25519() -> Result<()> {
+        roundtrip(Algorithm::MlDsa44Ed25519)?;
+        roundtrip(Algorithm::MlDsa65Ed25519)
+    }
+
+    #[test]
+    #[cfg(feature = "ml_dsa")]
+    fn test_mldsa_ecdsa() -> Result<()> {
+        roundtrip(Algorithm::MlDsa44EcdsaP256)?;
+        roundtrip(Algorithm::MlDsa87EcdsaP384)
+    }
+
+    #[test]
+    #[cfg(feature = "falcon")]
+    fn test_falcon() -> Result<()> {
+        roundtrip(Algorithm::Falcon512Ed25519)?;
+        roundtrip(Algorithm::Falcon1024EcdsaP384)
+    }
+
+    #[test]
+    fn test_algorithms() {
+        for (i, alg) in Algorithm::ALL.iter().enumerate() {
+            assert!(alg.label().starts_with("COMPSIG-"));
+            for other in &Algorithm::ALL[i + 1..] {
+                assert_ne!(alg.label(), other.label());
+                assert!(alg.oid().is_none() || alg.oid() != other.oid());
+            }
+            if let Some(oid) = alg.oid() {
+                assert_eq!(Algorithm::from_oid(oid), Some(*alg));
+            }
+        }
+    }
+}
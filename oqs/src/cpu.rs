@@ -0,0 +1,270 @@
+//! CPU extensions and the implementations liboqs selects with them
+//!
+//! Many algorithms come with implementations that use AVX2 or the ARMv8
+//! extensions, and can be several times faster than the portable ones.
+//! Distribution builds of liboqs pick one at runtime depending on the CPU
+//! (see [`BuildInfo::dist_build`](crate::BuildInfo::dist_build)).
+//!
+//! liboqs doesn't report which implementation it picked, so [`summary`] and
+//! the `implementation` methods of the algorithms are a best-effort estimate
+//! from `oqsconfig.h` and the detected extensions. Each implementation in liboqs
+//! checks its own set of extensions, which this module only approximates.
+//!
+//! ```
+//! use oqs::cpu::{self, CpuExt};
+//!
+//! oqs::init();
+//! println!("AVX2: {}", cpu::has_extension(CpuExt::Avx2));
+//! for (algorithm, implementation) in cpu::summary() {
+//!     println!("{algorithm}: {}", implementation.name);
+//! }
+//! ```
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::build_info::config;
+use crate::ffi::build_info::OQSCONFIG;
+use crate::ffi::common::{OQS_CPU_has_extension, OQS_CPU_EXT};
+use crate::{kem, sig};
+
+/// CPU extensions that liboqs detects
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum CpuExt {
+    /// Multi-precision add-carry instructions (x86)
+    Adx,
+    /// AES-NI (x86)
+    Aes,
+    /// AVX (x86)
+    Avx,
+    /// AVX2 (x86)
+    Avx2,
+    /// AVX-512 (x86)
+    Avx512,
+    /// BMI1 (x86)
+    Bmi1,
+    /// BMI2 (x86)
+    Bmi2,
+    /// Carry-less multiplication (x86)
+    Pclmulqdq,
+    /// Vectorized carry-less multiplication (x86)
+    Vpclmulqdq,
+    /// Population count (x86)
+    Popcnt,
+    /// SSE (x86)
+    Sse,
+    /// SSE2 (x86)
+    Sse2,
+    /// SSE3 (x86)
+    Sse3,
+    /// AES instructions (ARMv8)
+    ArmAes,
+    /// SHA-2 instructions (ARMv8)
+    ArmSha2,
+    /// SHA-3 instructions (ARMv8.2)
+    ArmSha3,
+    /// NEON (ARM)
+    ArmNeon,
+}
+
+impl CpuExt {
+    /// All extensions that liboqs detects
+    pub const ALL: &'static [CpuExt] = &[
+        CpuExt::Adx,
+        CpuExt::Aes,
+        CpuExt::Avx,
+        CpuExt::Avx2,
+        CpuExt::Avx512,
+        CpuExt::Bmi1,
+        CpuExt::Bmi2,
+        CpuExt::Pclmulqdq,
+        CpuExt::Vpclmulqdq,
+        CpuExt::Popcnt,
+        CpuExt::Sse,
+        CpuExt::Sse2,
+        CpuExt::Sse3,
+        CpuExt::ArmAes,
+        CpuExt::ArmSha2,
+        CpuExt::ArmSha3,
+        CpuExt::ArmNeon,
+    ];
+
+    fn to_ffi(self) -> OQS_CPU_EXT {
+        match self {
+            CpuExt::Adx => OQS_CPU_EXT::OQS_CPU_EXT_ADX,
+            CpuExt::Aes => OQS_CPU_EXT::OQS_CPU_EXT_AES,
+            CpuExt::Avx => OQS_CPU_EXT::OQS_CPU_EXT_AVX,
+            CpuExt::Avx2 => OQS_CPU_EXT::OQS_CPU_EXT_AVX2,
+            CpuExt::Avx512 => OQS_CPU_EXT::OQS_CPU_EXT_AVX512,
+            CpuExt::Bmi1 => OQS_CPU_EXT::OQS_CPU_EXT_BMI1,
+            CpuExt::Bmi2 => OQS_CPU_EXT::OQS_CPU_EXT_BMI2,
+            CpuExt::Pclmulqdq => OQS_CPU_EXT::OQS_CPU_EXT_PCLMULQDQ,
+            CpuExt::Vpclmulqdq => OQS_CPU_EXT::OQS_CPU_EXT_VPCLMULQDQ,
+            CpuExt::Popcnt => OQS_CPU_EXT::OQS_CPU_EXT_POPCNT,
+            CpuExt::Sse => OQS_CPU_EXT::OQS_CPU_EXT_SSE,
+            CpuExt::Sse2 => OQS_CPU_EXT::OQS_CPU_EXT_SSE2,
+            CpuExt::Sse3 => OQS_CPU_EXT::OQS_CPU_EXT_SSE3,
+            CpuExt::ArmAes => OQS_CPU_EXT::OQS_CPU_EXT_ARM_AES,
+            CpuExt::ArmSha2 => OQS_CPU_EXT::OQS_CPU_EXT_ARM_SHA2,
+            CpuExt::ArmSha3 => OQS_CPU_EXT::OQS_CPU_EXT_ARM_SHA3,
+            CpuExt::ArmNeon => OQS_CPU_EXT::OQS_CPU_EXT_ARM_NEON,
+        }
+    }
+
+    /// Name of the extension as liboqs spells it, such as `"AVX2"` or `"ARM_NEON"`
+    pub fn name(self) -> &'static str {
+        match self {
+            CpuExt::Adx => "ADX",
+            CpuExt::Aes => "AES",
+            CpuExt::Avx => "AVX",
+            CpuExt::Avx2 => "AVX2",
+            CpuExt::Avx512 => "AVX512",
+            CpuExt::Bmi1 => "BMI1",
+            CpuExt::Bmi2 => "BMI2",
+            CpuExt::Pclmulqdq => "PCLMULQDQ",
+            CpuExt::Vpclmulqdq => "VPCLMULQDQ",
+            CpuExt::Popcnt => "POPCNT",
+            CpuExt::Sse => "SSE",
+            CpuExt::Sse2 => "SSE2",
+            CpuExt::Sse3 => "SSE3",
+            CpuExt::ArmAes => "ARM_AES",
+            CpuExt::ArmSha2 => "ARM_SHA2",
+            CpuExt::ArmSha3 => "ARM_SHA3",
+            CpuExt::ArmNeon => "ARM_NEON",
+        }
+    }
+}
+
+impl fmt::Display for CpuExt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Does the CPU have `ext`, according to liboqs
+///
+/// Call [`init`](crate::init) first, which runs the detection.
+pub fn has_extension(ext: CpuExt) -> bool {
+    unsafe { OQS_CPU_has_extension(ext.to_ffi()) == 1 }
+}
+
+/// The extensions the CPU has, according to liboqs
+pub fn extensions() -> impl Iterator<Item = CpuExt> {
+    CpuExt::ALL
+        .iter()
+        .copied()
+        .filter(|ext| has_extension(*ext))
+}
+
+/// Implementation of an algorithm that liboqs presumably selects
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Implementation {
+    /// Name of the implementation in liboqs, such as `"avx2"` or `"aarch64"`,
+    /// or `"portable"`
+    pub name: &'static str,
+    /// CPU extensions that implementations of this name typically require
+    ///
+    /// Individual algorithms may check for more or fewer extensions.
+    pub requires: &'static [CpuExt],
+}
+
+impl Implementation {
+    /// The portable C implementation
+    pub const PORTABLE: Implementation = Implementation {
+        name: "portable",
+        requires: &[],
+    };
+
+    /// Does the implementation use CPU extensions
+    pub fn is_optimized(&self) -> bool {
+        *self != Self::PORTABLE
+    }
+
+    /// Does the CPU have the extensions in [`requires`](Self::requires)
+    pub fn is_supported(&self) -> bool {
+        self.requires.iter().all(|ext| has_extension(*ext))
+    }
+}
+
+/// Optimized implementations, in the order in which liboqs prefers them
+const OPTIMIZED: [Implementation; 4] = [
+    Implementation {
+        name: "avx512",
+        requires: &[CpuExt::Avx512],
+    },
+    Implementation {
+        name: "avx2",
+        requires: &[CpuExt::Avx2, CpuExt::Bmi2, CpuExt::Popcnt],
+    },
+    Implementation {
+        name: "x86_64",
+        requires: &[CpuExt::Avx2, CpuExt::Bmi2, CpuExt::Popcnt],
+    },
+    Implementation {
+        name: "aarch64",
+        requires: &[CpuExt::ArmNeon],
+    },
+];
+
+/// Estimate the implementation of the algorithm enabled by the `oqsconfig.h` macro `enable`
+///
+/// The optimized implementations are enabled by the same macro with the
+/// name of the implementation appended, such as `OQS_ENABLE_KEM_ml_kem_768_x86_64`.
+/// Distribution builds select them if the CPU has the required extensions,
+/// other builds always do. The extensions and the order of preference are
+/// those of [`OPTIMIZED`], not the checks of the algorithm in liboqs.
+pub(crate) fn select(enable: &str) -> Implementation {
+    let dist_build = config("OQS_DIST_BUILD").is_some();
+    OPTIMIZED
+        .into_iter()
+        .filter(|implementation| {
+            OQSCONFIG.iter().any(|(name, _)| {
+                name.strip_prefix(enable)
+                    .and_then(|suffix| suffix.strip_prefix('_'))
+                    == Some(implementation.name)
+            })
+        })
+        .find(|implementation| !dist_build || implementation.is_supported())
+        .unwrap_or(Implementation::PORTABLE)
+}
+
+/// An estimate of the implementation liboqs selects for each enabled KEM and signature scheme
+///
+/// Algorithms are listed by [`kem::Algorithm::name`] and [`sig::Algorithm::name`].
+/// See the [module documentation](self) for why this is an estimate.
+pub fn summary() -> Vec<(&'static str, Implementation)> {
+    let kems = kem::Algorithm::enabled().map(|alg| (alg.name(), alg.implementation()));
+    let sigs = sig::Algorithm::enabled().map(|alg| (alg.name(), alg.implementation()));
+    kems.chain(sigs).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_has_extension() {
+        crate::init();
+        let extensions: Vec<CpuExt> = extensions().collect();
+        #[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+        assert!(extensions.contains(&CpuExt::Sse2));
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        assert!(extensions.contains(&CpuExt::ArmNeon));
+        for ext in CpuExt::ALL {
+            assert_eq!(extensions.contains(ext), has_extension(*ext));
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        crate::init();
+        let summary = summary();
+        assert_eq!(
+            summary.len(),
+            kem::Algorithm::enabled().count() + sig::Algorithm::enabled().count()
+        );
+        for (_, implementation) in summary {
+            assert!(implementation.is_supported());
+        }
+    }
+}
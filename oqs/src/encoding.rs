@@ -0,0 +1,404 @@
+//! DER and PEM encoding of keys
+//!
+//! Public keys are encoded as `SubjectPublicKeyInfo`, with the raw public key
+//! as the subject public key. Secret keys are encoded as PKCS#8 `PrivateKeyInfo`,
+//! where the layout of the `privateKey` field depends on the algorithm,
+//! see [`PrivateKeyFormat`].
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use pkcs8::der::asn1::{BitStringRef, ContextSpecific, OctetStringRef};
+use pkcs8::der::pem::LineEnding;
+use pkcs8::der::zeroize::Zeroizing;
+use pkcs8::der::{Decode, Encode, Reader, SliceReader, Tag, TagNumber};
+use pkcs8::spki::{AlgorithmIdentifierRef, Document, SubjectPublicKeyInfoRef};
+use pkcs8::{ObjectIdentifier, PrivateKeyInfo, SecretDocument};
+
+use crate::{Error, Operation, Result};
+
+/// PEM label of `SubjectPublicKeyInfo` documents
+const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+/// PEM label of PKCS#8 documents
+const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+
+/// Layout of the `privateKey` field of PKCS#8 documents
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum PrivateKeyFormat {
+    /// The `ML-KEM-PrivateKey` and `ML-DSA-PrivateKey` choice of the IETF LAMPS drafts
+    ///
+    /// We encode the `expandedKey` alternative, as liboqs does not keep the seed.
+    /// Decoding also accepts the `seed` and `both` alternatives.
+    Ietf,
+    /// An `OCTET STRING` holding the secret key followed by the public key, as used by oqs-provider
+    ///
+    /// Decoding also accepts the secret key on its own.
+    OqsProvider,
+}
+
+/// Secret key material found in a PKCS#8 document
+pub(crate) enum DecodedSecretKey<'a> {
+    /// The secret key in the format of liboqs
+    Expanded(&'a [u8]),
+    /// The seed the keypair is derived from
+    Seed(&'a [u8]),
+}
+
+impl From<pkcs8::der::Error> for Error {
+    fn from(_: pkcs8::der::Error) -> Self {
+        Error::InvalidEncoding
+    }
+}
+
+impl From<pkcs8::spki::Error> for Error {
+    fn from(_: pkcs8::spki::Error) -> Self {
+        Error::InvalidEncoding
+    }
+}
+
+impl From<pkcs8::Error> for Error {
+    fn from(_: pkcs8::Error) -> Self {
+        Error::InvalidEncoding
+    }
+}
+
+fn algorithm_identifier(oid: ObjectIdentifier) -> AlgorithmIdentifierRef<'static> {
+    AlgorithmIdentifierRef {
+        oid,
+        parameters: None,
+    }
+}
+
+/// Fails with [`Error::WrongLength`] if a decoded key of `algorithm` has the wrong length
+fn check_length(algorithm: &'static str, expected: usize, actual: usize) -> Result<()> {
+    if actual != expected {
+        return Err(Error::WrongLength {
+            operation: Operation::Decode,
+            algorithm,
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Check that a document is for the algorithm with `oid`, which has no parameters
+fn check_algorithm(algorithm: &AlgorithmIdentifierRef<'_>, oid: ObjectIdentifier) -> Result<()> {
+    if algorithm.oid != oid {
+        Err(Error::AlgorithmMismatch)
+    } else if algorithm.parameters.is_some() {
+        Err(Error::InvalidEncoding)
+    } else {
+        Ok(())
+    }
+}
+
+/// Encode `pk` as DER `SubjectPublicKeyInfo`
+pub(crate) fn public_key_to_der(oid: ObjectIdentifier, pk: &[u8]) -> Result<Document> {
+    let spki = SubjectPublicKeyInfoRef {
+        algorithm: algorithm_identifier(oid),
+        subject_public_key: BitStringRef::from_bytes(pk)?,
+    };
+    Ok(Document::try_from(spki)?)
+}
+
+/// Encode `pk` as PEM `SubjectPublicKeyInfo`
+pub(crate) fn public_key_to_pem(
+    oid: ObjectIdentifier,
+    pk: &[u8],
+    line_ending: LineEnding,
+) -> Result<String> {
+    Ok(public_key_to_der(oid, pk)?.to_pem(PUBLIC_KEY_LABEL, line_ending)?)
+}
+
+/// Decode the public key from a `SubjectPublicKeyInfo` for the algorithm with `oid`
+pub(crate) fn public_key_from_spki<'a>(
+    oid: ObjectIdentifier,
+    algorithm: &'static str,
+    spki: &SubjectPublicKeyInfoRef<'a>,
+    length: usize,
+) -> Result<&'a [u8]> {
+    check_algorithm(&spki.algorithm, oid)?;
+    let pk = spki
+        .subject_public_key
+        .as_bytes()
+        .ok_or(Error::InvalidEncoding)?;
+    check_length(algorithm, length, pk.len())?;
+    Ok(pk)
+}
+
+/// Decode the public key from a DER `SubjectPublicKeyInfo` for the algorithm with `oid`
+pub(crate) fn public_key_from_der(
+    oid: ObjectIdentifier,
+    algorithm: &'static str,
+    der: &[u8],
+    length: usize,
+) -> Result<Vec<u8>> {
+    let spki = SubjectPublicKeyInfoRef::try_from(der)?;
+    Ok(public_key_from_spki(oid, algorithm, &spki, length)?.to_vec())
+}
+
+/// Decode the public key from a PEM `SubjectPublicKeyInfo` for the algorithm with `oid`
+pub(crate) fn public_key_from_pem(
+    oid: ObjectIdentifier,
+    algorithm: &'static str,
+    pem: &str,
+    length: usize,
+) -> Result<Vec<u8>> {
+    let (label, document) = Document::from_pem(pem)?;
+    if label != PUBLIC_KEY_LABEL {
+        return Err(Error::InvalidEncoding);
+    }
+    public_key_from_der(oid, algorithm, document.as_bytes(), length)
+}
+
+/// Encode `sk` as DER PKCS#8
+pub(crate) fn secret_key_to_der(
+    oid: ObjectIdentifier,
+    format: PrivateKeyFormat,
+    sk: &[u8],
+    pk: &[u8],
+) -> Result<SecretDocument> {
+    let contents = match format {
+        PrivateKeyFormat::Ietf => Zeroizing::new(sk.to_vec()),
+        PrivateKeyFormat::OqsProvider => Zeroizing::new([sk, pk].concat()),
+    };
+    let private_key = Zeroizing::new(OctetStringRef::new(&contents)?.to_der()?);
+    let info = PrivateKeyInfo::new(algorithm_identifier(oid), &private_key);
+    Ok(SecretDocument::try_from(info)?)
+}
+
+/// Encode `sk` as PEM PKCS#8
+pub(crate) fn secret_key_to_pem(
+    oid: ObjectIdentifier,
+    format: PrivateKeyFormat,
+    sk: &[u8],
+    pk: &[u8],
+    line_ending: LineEnding,
+) -> Result<Zeroizing<String>> {
+    Ok(secret_key_to_der(oid, format, sk, pk)?.to_pem(PRIVATE_KEY_LABEL, line_ending)?)
+}
+
+/// Decode the secret key from a DER PKCS#8 document for the algorithm with `oid`
+///
+/// Passes the secret key material to `f`, so that it does not outlive the document.
+pub(crate) fn secret_key_from_der<T>(
+    oid: ObjectIdentifier,
+    algorithm: &'static str,
+    format: PrivateKeyFormat,
+    der: &[u8],
+    lengths: (usize, usize),
+    f: impl FnOnce(DecodedSecretKey<'_>) -> Result<T>,
+) -> Result<T> {
+    let (sk_length, pk_length) = lengths;
+    let info = PrivateKeyInfo::try_from(der)?;
+    check_algorithm(&info.algorithm, oid)?;
+    let mut reader = SliceReader::new(info.private_key)?;
+    let decoded = match format {
+        PrivateKeyFormat::Ietf => match reader.peek_tag()? {
+            Tag::OctetString => {
+                DecodedSecretKey::Expanded(OctetStringRef::decode(&mut reader)?.as_bytes())
+            }
+            Tag::Sequence => reader.sequence(|reader| {
+                let _seed = OctetStringRef::decode(reader)?;
+                Ok(DecodedSecretKey::Expanded(
+                    OctetStringRef::decode(reader)?.as_bytes(),
+                ))
+            })?,
+            _ => {
+                // seed [0] IMPLICIT OCTET STRING
+                let seed = ContextSpecific::<OctetStringRef<'_>>::decode_implicit(
+                    &mut reader,
+                    TagNumber::N0,
+                )?
+                .ok_or(Error::InvalidEncoding)?;
+                DecodedSecretKey::Seed(seed.value.as_bytes())
+            }
+        },
+        PrivateKeyFormat::OqsProvider => {
+            let contents = OctetStringRef::decode(&mut reader)?.as_bytes();
+            if contents.len() == sk_length + pk_length {
+                DecodedSecretKey::Expanded(&contents[..sk_length])
+            } else {
+                DecodedSecretKey::Expanded(contents)
+            }
+        }
+    };
+    reader.finish(())?;
+    if let DecodedSecretKey::Expanded(sk) = &decoded {
+        check_length(algorithm, sk_length, sk.len())?;
+    }
+    f(decoded)
+}
+
+/// Decode the secret key from a PEM PKCS#8 document for the algorithm with `oid`
+pub(crate) fn secret_key_from_pem<T>(
+    oid: ObjectIdentifier,
+    algorithm: &'static str,
+    format: PrivateKeyFormat,
+    pem: &str,
+    lengths: (usize, usize),
+    f: impl FnOnce(DecodedSecretKey<'_>) -> Result<T>,
+) -> Result<T> {
+    let (label, document) = SecretDocument::from_pem(pem)?;
+    if label != PRIVATE_KEY_LABEL {
+        return Err(Error::InvalidEncoding);
+    }
+    secret_key_from_der(oid, algorithm, format, document.as_bytes(), lengths, f)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.22554.5.6.1");
+    const OTHER_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.22554.5.6.2");
+    const ALGORITHM: &str = "test";
+
+    fn document(private_key: &[u8]) -> SecretDocument {
+        SecretDocument::try_from(PrivateKeyInfo::new(algorithm_identifier(OID), private_key))
+            .unwrap()
+    }
+
+    fn decode(format: PrivateKeyFormat, der: &[u8], lengths: (usize, usize)) -> Result<Vec<u8>> {
+        secret_key_from_der(
+            OID,
+            ALGORITHM,
+            format,
+            der,
+            lengths,
+            |decoded| match decoded {
+                DecodedSecretKey::Expanded(sk) => Ok([b"sk:", sk].concat()),
+                DecodedSecretKey::Seed(seed) => Ok([b"seed:", seed].concat()),
+            },
+        )
+    }
+
+    #[test]
+    fn test_public_key() -> Result<()> {
+        let pk = [1u8; 32];
+        let der = public_key_to_der(OID, &pk)?;
+        assert_eq!(public_key_from_der(OID, ALGORITHM, der.as_bytes(), 32)?, pk);
+        assert!(matches!(
+            public_key_from_der(OTHER_OID, ALGORITHM, der.as_bytes(), 32),
+            Err(Error::AlgorithmMismatch)
+        ));
+        assert!(matches!(
+            public_key_from_der(OID, ALGORITHM, der.as_bytes(), 31),
+            Err(Error::WrongLength {
+                operation: Operation::Decode,
+                expected: 31,
+                actual: 32,
+                ..
+            })
+        ));
+        let pem = public_key_to_pem(OID, &pk, LineEnding::LF)?;
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----\n"));
+        assert_eq!(public_key_from_pem(OID, ALGORITHM, &pem, 32)?, pk);
+        let pem = pem.replace("PUBLIC KEY", "PRIVATE KEY");
+        assert!(matches!(
+            public_key_from_pem(OID, ALGORITHM, &pem, 32),
+            Err(Error::InvalidEncoding)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ietf_expanded_key() -> Result<()> {
+        let der = secret_key_to_der(OID, PrivateKeyFormat::Ietf, &[2; 4], &[1; 8])?;
+        // privateKey holds OCTET STRING { 02 02 02 02 }
+        assert!(der
+            .as_bytes()
+            .ends_with(&[0x04, 0x06, 0x04, 0x04, 2, 2, 2, 2]));
+        assert_eq!(
+            decode(PrivateKeyFormat::Ietf, der.as_bytes(), (4, 8))?,
+            b"sk:\x02\x02\x02\x02"
+        );
+        assert!(matches!(
+            decode(PrivateKeyFormat::Ietf, der.as_bytes(), (5, 8)),
+            Err(Error::WrongLength {
+                expected: 5,
+                actual: 4,
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ietf_seed() -> Result<()> {
+        let der = document(&[0x80, 0x02, 7, 7]);
+        assert_eq!(
+            decode(PrivateKeyFormat::Ietf, der.as_bytes(), (4, 8))?,
+            b"seed:\x07\x07"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ietf_both() -> Result<()> {
+        let der = document(&[0x30, 0x08, 0x04, 0x02, 7, 7, 0x04, 0x02, 2, 2]);
+        assert_eq!(
+            decode(PrivateKeyFormat::Ietf, der.as_bytes(), (2, 8))?,
+            b"sk:\x02\x02"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ietf_trailing_data() {
+        let der = document(&[0x04, 0x02, 2, 2, 0x04, 0x00]);
+        assert!(matches!(
+            decode(PrivateKeyFormat::Ietf, der.as_bytes(), (2, 8)),
+            Err(Error::InvalidEncoding)
+        ));
+    }
+
+    #[test]
+    fn test_oqs_provider() -> Result<()> {
+        let der = secret_key_to_der(OID, PrivateKeyFormat::OqsProvider, &[2; 4], &[1; 2])?;
+        assert!(der
+            .as_bytes()
+            .ends_with(&[0x04, 0x08, 0x04, 0x06, 2, 2, 2, 2, 1, 1]));
+        let sk = decode(PrivateKeyFormat::OqsProvider, der.as_bytes(), (4, 2))?;
+        assert_eq!(sk, b"sk:\x02\x02\x02\x02");
+        // the public key may be left out
+        let der = document(&[0x04, 0x04, 2, 2, 2, 2]);
+        let sk = decode(PrivateKeyFormat::OqsProvider, der.as_bytes(), (4, 2))?;
+        assert_eq!(sk, b"sk:\x02\x02\x02\x02");
+        let pem = secret_key_to_pem(
+            OID,
+            PrivateKeyFormat::OqsProvider,
+            &[2; 4],
+            &[1; 2],
+            LineEnding::LF,
+        )?;
+        let sk = secret_key_from_pem(
+            OID,
+            ALGORITHM,
+            PrivateKeyFormat::OqsProvider,
+            &pem,
+            (4, 2),
+            |d| match d {
+                DecodedSecretKey::Expanded(sk) => Ok(sk.to_vec()),
+                DecodedSecretKey::Seed(_) => Err(Error::Unsupported),
+            },
+        )?;
+        assert_eq!(sk, [2; 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_algorithm_mismatch() -> Result<()> {
+        let der = secret_key_to_der(OID, PrivateKeyFormat::Ietf, &[2; 4], &[1; 8])?;
+        let result = secret_key_from_der(
+            OTHER_OID,
+            ALGORITHM,
+            PrivateKeyFormat::Ietf,
+            der.as_bytes(),
+            (4, 8),
+            |_| Ok(()),
+        );
+        assert!(matches!(result, Err(Error::AlgorithmMismatch)));
+        Ok(())
+    }
+}
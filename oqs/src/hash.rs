@@ -0,0 +1,653 @@
+//! SHA-2, SHA-3 and SHAKE
+//!
+//! These are the hash functions liboqs uses internally, so they run on the
+//! same implementation as the KEMs and signature schemes: OpenSSL if liboqs
+//! was built with it, and otherwise liboqs' own code with the CPU
+//! optimizations it was built with.
+//!
+//! Every hash function has a one-shot function, such as [`sha256`], and an
+//! incremental hasher, such as [`Sha256`]:
+//!
+//! ```
+//! use oqs::hash::{self, Sha3_256, Shake128};
+//!
+//! oqs::init();
+//! let mut hasher = Sha3_256::new();
+//! hasher.update(b"hello ");
+//! hasher.update(b"world");
+//! assert_eq!(hasher.finalize(), hash::sha3_256(b"hello world"));
+//!
+//! let mut shake = Shake128::new();
+//! shake.update(b"hello world");
+//! let mut reader = shake.finalize_xof();
+//! let mut output = [0u8; 64];
+//! reader.squeeze(&mut output[..10]);
+//! reader.squeeze(&mut output[10..]);
+//! let mut expected = [0u8; 64];
+//! hash::shake128(b"hello world", &mut expected);
+//! assert_eq!(output, expected);
+//! ```
+//!
+//! With the `digest` feature, the hashers implement the traits of the
+//! RustCrypto `digest` crate, so they can be plugged into HMAC, HKDF and
+//! other generic constructions.
+use core::fmt;
+use core::mem::{self, ManuallyDrop};
+
+use crate::ffi::{sha2 as ffi_sha2, sha3 as ffi_sha3};
+
+/// Defines a SHA-2 hasher
+///
+/// liboqs only absorbs whole blocks into SHA-2 states, so the hasher
+/// buffers the input until it has a full block.
+/// Unlike for SHA-3, finalizing a SHA-2 state releases it.
+macro_rules! sha2 {
+    ($name:ident, $fn:ident, $prefix:ident, $bits:literal, $len:literal, $block:literal) => {
+        paste::item! {
+            #[doc = "Compute the SHA-" $bits " hash of `data`"]
+            pub fn $fn(data: &[u8]) -> [u8; $len] {
+                let mut output = [0u8; $len];
+                unsafe { ffi_sha2::[<$prefix>](output.as_mut_ptr(), data.as_ptr(), data.len()) };
+                output
+            }
+
+            #[doc = "Incremental SHA-" $bits " hasher"]
+            pub struct $name {
+                state: ffi_sha2::[<$prefix _ctx>],
+                buffer: [u8; $block],
+                buffered: usize,
+            }
+
+            impl $name {
+                /// Length of the hash in bytes
+                pub const OUTPUT_LENGTH: usize = $len;
+
+                /// Block length of the hash in bytes
+                pub const BLOCK_LENGTH: usize = $block;
+
+                /// Start a new hash computation
+                pub fn new() -> Self {
+                    let mut state = unsafe { mem::zeroed() };
+                    unsafe { ffi_sha2::[<$prefix _inc_init>](&mut state) };
+                    Self {
+                        state,
+                        buffer: [0u8; $block],
+                        buffered: 0,
+                    }
+                }
+
+                /// Absorb more data
+                pub fn update(&mut self, mut data: &[u8]) {
+                    if self.buffered > 0 {
+                        let take = ($block - self.buffered).min(data.len());
+                        self.buffer[self.buffered..self.buffered + take]
+                            .copy_from_slice(&data[..take]);
+                        self.buffered += take;
+                        data = &data[take..];
+                        if self.buffered < $block {
+                            return;
+                        }
+                        let (state, buffer) = (&mut self.state, self.buffer.as_ptr());
+                        unsafe { ffi_sha2::[<$prefix _inc_blocks>](state, buffer, 1) };
+                        self.buffered = 0;
+                    }
+                    let blocks = data.len() / $block;
+                    if blocks > 0 {
+                        let state = &mut self.state;
+                        unsafe { ffi_sha2::[<$prefix _inc_blocks>](state, data.as_ptr(), blocks) };
+                    }
+                    let rest = &data[blocks * $block..];
+                    self.buffer[..rest.len()].copy_from_slice(rest);
+                    self.buffered = rest.len();
+                }
+
+                /// Finish the computation and return the hash
+                pub fn finalize(self) -> [u8; $len] {
+                    // liboqs releases the state when it is finalized
+                    let mut this = ManuallyDrop::new(self);
+                    let mut output = [0u8; $len];
+                    let (buffer, buffered) = (this.buffer.as_ptr(), this.buffered);
+                    unsafe {
+                        ffi_sha2::[<$prefix _inc_finalize>](
+                            output.as_mut_ptr(),
+                            &mut this.state,
+                            buffer,
+                            buffered,
+                        )
+                    };
+                    output
+                }
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl Clone for $name {
+                fn clone(&self) -> Self {
+                    // liboqs allocates the state of the clone
+                    let mut state = unsafe { mem::zeroed() };
+                    unsafe { ffi_sha2::[<$prefix _inc_ctx_clone>](&mut state, &self.state) };
+                    Self {
+                        state,
+                        buffer: self.buffer,
+                        buffered: self.buffered,
+                    }
+                }
+            }
+
+            impl Drop for $name {
+                fn drop(&mut self) {
+                    unsafe { ffi_sha2::[<$prefix _inc_ctx_release>](&mut self.state) };
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.debug_struct(stringify!($name)).finish_non_exhaustive()
+                }
+            }
+
+            unsafe impl Send for $name {}
+            unsafe impl Sync for $name {}
+
+            #[cfg(feature = "digest")]
+            impl digest::HashMarker for $name {}
+
+            #[cfg(feature = "digest")]
+            impl digest::OutputSizeUser for $name {
+                type OutputSize = digest::consts::[<U $len>];
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::core_api::BlockSizeUser for $name {
+                type BlockSize = digest::consts::[<U $block>];
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::Update for $name {
+                fn update(&mut self, data: &[u8]) {
+                    $name::update(self, data)
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::FixedOutput for $name {
+                fn finalize_into(self, out: &mut digest::Output<Self>) {
+                    out.copy_from_slice(&$name::finalize(self))
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::Reset for $name {
+                fn reset(&mut self) {
+                    *self = Self::new();
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::FixedOutputReset for $name {
+                fn finalize_into_reset(&mut self, out: &mut digest::Output<Self>) {
+                    out.copy_from_slice(&mem::take(self).finalize())
+                }
+            }
+        }
+    };
+}
+
+sha2!(Sha256, sha256, OQS_SHA2_sha256, 256, 32, 64);
+sha2!(Sha384, sha384, OQS_SHA2_sha384, 384, 48, 128);
+sha2!(Sha512, sha512, OQS_SHA2_sha512, 512, 64, 128);
+
+/// Defines a SHA-3 hasher
+macro_rules! sha3 {
+    ($name:ident, $fn:ident, $prefix:ident, $bits:literal, $len:literal, $rate:literal) => {
+        paste::item! {
+            #[doc = "Compute the SHA3-" $bits " hash of `data`"]
+            pub fn $fn(data: &[u8]) -> [u8; $len] {
+                let mut output = [0u8; $len];
+                unsafe { ffi_sha3::[<$prefix>](output.as_mut_ptr(), data.as_ptr(), data.len()) };
+                output
+            }
+
+            #[doc = "Incremental SHA3-" $bits " hasher"]
+            pub struct $name {
+                state: ffi_sha3::[<$prefix _inc_ctx>],
+            }
+
+            impl $name {
+                /// Length of the hash in bytes
+                pub const OUTPUT_LENGTH: usize = $len;
+
+                /// Rate of the sponge in bytes
+                pub const BLOCK_LENGTH: usize = $rate;
+
+                /// Start a new hash computation
+                pub fn new() -> Self {
+                    let mut state = unsafe { mem::zeroed() };
+                    unsafe { ffi_sha3::[<$prefix _inc_init>](&mut state) };
+                    Self { state }
+                }
+
+                /// Absorb more data
+                pub fn update(&mut self, data: &[u8]) {
+                    let state = &mut self.state;
+                    unsafe { ffi_sha3::[<$prefix _inc_absorb>](state, data.as_ptr(), data.len()) };
+                }
+
+                /// Finish the computation and return the hash
+                pub fn finalize(mut self) -> [u8; $len] {
+                    let mut output = [0u8; $len];
+                    let state = &mut self.state;
+                    unsafe { ffi_sha3::[<$prefix _inc_finalize>](output.as_mut_ptr(), state) };
+                    output
+                }
+
+                /// Discard all absorbed data
+                pub fn reset(&mut self) {
+                    unsafe { ffi_sha3::[<$prefix _inc_ctx_reset>](&mut self.state) };
+                }
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl Clone for $name {
+                fn clone(&self) -> Self {
+                    // liboqs copies into an initialized state
+                    let mut clone = Self::new();
+                    unsafe { ffi_sha3::[<$prefix _inc_ctx_clone>](&mut clone.state, &self.state) };
+                    clone
+                }
+            }
+
+            impl Drop for $name {
+                fn drop(&mut self) {
+                    unsafe { ffi_sha3::[<$prefix _inc_ctx_release>](&mut self.state) };
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.debug_struct(stringify!($name)).finish_non_exhaustive()
+                }
+            }
+
+            unsafe impl Send for $name {}
+            unsafe impl Sync for $name {}
+
+            #[cfg(feature = "digest")]
+            impl digest::HashMarker for $name {}
+
+            #[cfg(feature = "digest")]
+            impl digest::OutputSizeUser for $name {
+                type OutputSize = digest::consts::[<U $len>];
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::core_api::BlockSizeUser for $name {
+                type BlockSize = digest::consts::[<U $rate>];
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::Update for $name {
+                fn update(&mut self, data: &[u8]) {
+                    $name::update(self, data)
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::FixedOutput for $name {
+                fn finalize_into(self, out: &mut digest::Output<Self>) {
+                    out.copy_from_slice(&$name::finalize(self))
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::Reset for $name {
+                fn reset(&mut self) {
+                    $name::reset(self)
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::FixedOutputReset for $name {
+                fn finalize_into_reset(&mut self, out: &mut digest::Output<Self>) {
+                    let state = &mut self.state;
+                    unsafe { ffi_sha3::[<$prefix _inc_finalize>](out.as_mut_ptr(), state) };
+                    $name::reset(self)
+                }
+            }
+        }
+    };
+}
+
+sha3!(Sha3_256, sha3_256, OQS_SHA3_sha3_256, 256, 32, 136);
+sha3!(Sha3_384, sha3_384, OQS_SHA3_sha3_384, 384, 48, 104);
+sha3!(Sha3_512, sha3_512, OQS_SHA3_sha3_512, 512, 64, 72);
+
+/// Defines a SHAKE hasher and the reader for its output
+macro_rules! shake {
+    ($name:ident, $fn:ident, $prefix:ident, $bits:literal, $rate:literal) => {
+        paste::item! {
+            #[doc = "Fill `output` with the SHAKE" $bits " output for `data`"]
+            pub fn $fn(data: &[u8], output: &mut [u8]) {
+                unsafe {
+                    ffi_sha3::[<$prefix>](
+                        output.as_mut_ptr(),
+                        output.len(),
+                        data.as_ptr(),
+                        data.len(),
+                    )
+                };
+            }
+
+            #[doc = "Incremental SHAKE" $bits " hasher"]
+            ///
+            /// Absorb the input with `update`, then squeeze any amount of output
+            /// from the reader returned by `finalize_xof`.
+            pub struct $name {
+                state: ffi_sha3::[<$prefix _inc_ctx>],
+            }
+
+            impl $name {
+                /// Rate of the sponge in bytes
+                pub const BLOCK_LENGTH: usize = $rate;
+
+                /// Start a new hash computation
+                pub fn new() -> Self {
+                    let mut state = unsafe { mem::zeroed() };
+                    unsafe { ffi_sha3::[<$prefix _inc_init>](&mut state) };
+                    Self { state }
+                }
+
+                /// Absorb more data
+                pub fn update(&mut self, data: &[u8]) {
+                    let state = &mut self.state;
+                    unsafe { ffi_sha3::[<$prefix _inc_absorb>](state, data.as_ptr(), data.len()) };
+                }
+
+                /// Finish absorbing and return a reader for the output
+                pub fn finalize_xof(mut self) -> [<$name Reader>] {
+                    unsafe { ffi_sha3::[<$prefix _inc_finalize>](&mut self.state) };
+                    [<$name Reader>] { hasher: self }
+                }
+
+                /// Discard all absorbed data
+                pub fn reset(&mut self) {
+                    unsafe { ffi_sha3::[<$prefix _inc_ctx_reset>](&mut self.state) };
+                }
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl Clone for $name {
+                fn clone(&self) -> Self {
+                    // liboqs copies into an initialized state
+                    let mut clone = Self::new();
+                    unsafe { ffi_sha3::[<$prefix _inc_ctx_clone>](&mut clone.state, &self.state) };
+                    clone
+                }
+            }
+
+            impl Drop for $name {
+                fn drop(&mut self) {
+                    unsafe { ffi_sha3::[<$prefix _inc_ctx_release>](&mut self.state) };
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.debug_struct(stringify!($name)).finish_non_exhaustive()
+                }
+            }
+
+            unsafe impl Send for $name {}
+            unsafe impl Sync for $name {}
+
+            #[doc = "Reader for the output of [`" $name "`]"]
+            pub struct [<$name Reader>] {
+                hasher: $name,
+            }
+
+            impl [<$name Reader>] {
+                /// Fill `output` with the next bytes of output
+                pub fn squeeze(&mut self, output: &mut [u8]) {
+                    let state = &mut self.hasher.state;
+                    unsafe {
+                        ffi_sha3::[<$prefix _inc_squeeze>](output.as_mut_ptr(), output.len(), state)
+                    };
+                }
+            }
+
+            impl fmt::Debug for [<$name Reader>] {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.debug_struct(stringify!([<$name Reader>])).finish_non_exhaustive()
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::HashMarker for $name {}
+
+            #[cfg(feature = "digest")]
+            impl digest::core_api::BlockSizeUser for $name {
+                type BlockSize = digest::consts::[<U $rate>];
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::Update for $name {
+                fn update(&mut self, data: &[u8]) {
+                    $name::update(self, data)
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::ExtendableOutput for $name {
+                type Reader = [<$name Reader>];
+
+                fn finalize_xof(self) -> Self::Reader {
+                    $name::finalize_xof(self)
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::Reset for $name {
+                fn reset(&mut self) {
+                    $name::reset(self)
+                }
+            }
+
+            #[cfg(feature = "digest")]
+            impl digest::XofReader for [<$name Reader>] {
+                fn read(&mut self, buffer: &mut [u8]) {
+                    self.squeeze(buffer)
+                }
+            }
+        }
+    };
+}
+
+shake!(Shake128, shake128, OQS_SHA3_shake128, 128, 168);
+shake!(Shake256, shake256, OQS_SHA3_shake256, 256, 136);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Message that spans several blocks of every hash function
+    fn message() -> Vec<u8> {
+        (0..1000u32).map(|i| (i * 7) as u8).collect()
+    }
+
+    #[test]
+    fn test_known_answers() {
+        crate::init();
+        assert_eq!(
+            sha256(b"abc")[..],
+            unhex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            sha384(b"abc")[..],
+            unhex(concat!(
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed",
+                "8086072ba1e7cc2358baeca134c825a7"
+            ))
+        );
+        assert_eq!(
+            sha512(b"abc")[..],
+            unhex(concat!(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a",
+                "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+            ))
+        );
+        assert_eq!(
+            sha3_256(b"abc")[..],
+            unhex("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+        );
+        assert_eq!(
+            sha3_384(b"abc")[..],
+            unhex(concat!(
+                "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b2",
+                "98d88cea927ac7f539f1edf228376d25"
+            ))
+        );
+        assert_eq!(
+            sha3_512(b"abc")[..],
+            unhex(concat!(
+                "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e",
+                "10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+            ))
+        );
+        let mut output = [0u8; 32];
+        shake128(b"", &mut output);
+        assert_eq!(
+            output[..],
+            unhex("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26")
+        );
+        let mut output = [0u8; 64];
+        shake256(b"", &mut output);
+        assert_eq!(
+            output[..],
+            unhex(concat!(
+                "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f",
+                "d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be"
+            ))
+        );
+    }
+
+    macro_rules! test_incremental {
+        ($name:ident, $hasher:ident, $fn:ident) => {
+            #[test]
+            fn $name() {
+                crate::init();
+                let message = message();
+                for chunk in [1, 3, 64, 100, 1000] {
+                    let mut hasher = $hasher::new();
+                    for part in message.chunks(chunk) {
+                        hasher.update(part);
+                    }
+                    let clone = hasher.clone();
+                    assert_eq!(hasher.finalize(), $fn(&message));
+                    assert_eq!(clone.finalize(), $fn(&message));
+                }
+                assert_eq!($hasher::default().finalize(), $fn(b""));
+            }
+        };
+    }
+
+    test_incremental!(test_sha256_incremental, Sha256, sha256);
+    test_incremental!(test_sha384_incremental, Sha384, sha384);
+    test_incremental!(test_sha512_incremental, Sha512, sha512);
+    test_incremental!(test_sha3_256_incremental, Sha3_256, sha3_256);
+    test_incremental!(test_sha3_384_incremental, Sha3_384, sha3_384);
+    test_incremental!(test_sha3_512_incremental, Sha3_512, sha3_512);
+
+    #[test]
+    fn test_sha3_reset() {
+        crate::init();
+        let mut hasher = Sha3_256::new();
+        hasher.update(b"discarded");
+        hasher.reset();
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize(), sha3_256(b"abc"));
+    }
+
+    macro_rules! test_shake {
+        ($name:ident, $hasher:ident, $fn:ident) => {
+            #[test]
+            fn $name() {
+                crate::init();
+                let message = message();
+                let mut expected = vec![0u8; 500];
+                $fn(&message, &mut expected);
+                for chunk in [1, 7, 168, 500] {
+                    let mut hasher = $hasher::new();
+                    for part in message.chunks(chunk) {
+                        hasher.update(part);
+                    }
+                    let mut reader = hasher.clone().finalize_xof();
+                    let mut output = vec![0u8; 500];
+                    for part in output.chunks_mut(chunk) {
+                        reader.squeeze(part);
+                    }
+                    assert_eq!(output, expected);
+                    hasher.reset();
+                    hasher.update(&message);
+                    let mut output = vec![0u8; 500];
+                    hasher.finalize_xof().squeeze(&mut output);
+                    assert_eq!(output, expected);
+                }
+            }
+        };
+    }
+
+    test_shake!(test_shake128_incremental, Shake128, shake128);
+    test_shake!(test_shake256_incremental, Shake256, shake256);
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn test_digest_traits() {
+        use digest::{Digest, ExtendableOutput, Update, XofReader};
+
+        crate::init();
+        let message = message();
+        assert_eq!(
+            <Sha256 as Digest>::digest(&message)[..],
+            sha2::Sha256::digest(&message)[..]
+        );
+        assert_eq!(
+            <Sha512 as Digest>::digest(&message)[..],
+            sha2::Sha512::digest(&message)[..]
+        );
+        let mut hasher = <Sha3_384 as Digest>::new();
+        Digest::update(&mut hasher, &message);
+        assert_eq!(hasher.finalize_reset()[..], sha3_384(&message));
+        assert_eq!(hasher.finalize()[..], sha3_384(b""));
+
+        let mut hasher = Shake256::default();
+        Update::update(&mut hasher, &message);
+        let mut output = [0u8; 100];
+        ExtendableOutput::finalize_xof(hasher).read(&mut output);
+        let mut expected = [0u8; 100];
+        shake256(&message, &mut expected);
+        assert_eq!(output, expected);
+    }
+}
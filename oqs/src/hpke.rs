@@ -0,0 +1,986 @@
+//! Hybrid Public Key Encryption
+//!
+//! Implements HPKE as specified in [RFC 9180](https://www.rfc-editor.org/rfc/rfc9180)
+//! with ML-KEM and [X-Wing](crate::hybrid::XWing) as the KEM, in the base and PSK modes.
+//! The KEMs are used directly, without the `DHKEM` construction of RFC 9180,
+//! as in [draft-ietf-hpke-pq](https://datatracker.ietf.org/doc/draft-ietf-hpke-pq/):
+//! they have its KEM identifiers, secret keys are the seeds of the KEM key generation,
+//! and [`Hpke::derive_keypair`] implements its `DeriveKeyPair`.
+//!
+//! See [`Hpke`] for the single-shot API and [`Context`] to encrypt several
+//! messages or to export secrets.
+use alloc::vec::Vec;
+
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes128Gcm, Aes256Gcm};
+use chacha20poly1305::ChaCha20Poly1305;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Sha384};
+use sha3::digest::{ExtendableOutput, Update};
+use sha3::Shake256;
+
+use crate::hybrid::XWing;
+use crate::kem::{self, Kem};
+use crate::newtype_buffer;
+use crate::*;
+
+newtype_buffer!(PublicKey, PublicKeyRef);
+newtype_buffer!(secret SecretKey, SecretKeyRef);
+newtype_buffer!(EncapsulatedKey, EncapsulatedKeyRef);
+
+/// Name of HPKE in errors of the key schedule
+const HPKE: &str = "HPKE";
+
+const MODE_BASE: u8 = 0x00;
+const MODE_PSK: u8 = 0x01;
+
+/// Length of the AEAD nonces
+const NONCE_LENGTH: usize = 12;
+
+/// KEMs for HPKE
+///
+/// Optional support for `serde` if that feature is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum KemId {
+    /// ML-KEM-512
+    MlKem512,
+    /// ML-KEM-768
+    MlKem768,
+    /// ML-KEM-1024
+    MlKem1024,
+    /// X-Wing, see [`XWing`]
+    XWing,
+}
+
+impl KemId {
+    /// Returns the HPKE identifier of the KEM
+    pub fn id(self) -> u16 {
+        match self {
+            KemId::MlKem512 => 0x0040,
+            KemId::MlKem768 => 0x0041,
+            KemId::MlKem1024 => 0x0042,
+            KemId::XWing => 0x647a,
+        }
+    }
+
+    /// Look up a KEM by its HPKE identifier
+    pub fn from_id(id: u16) -> Option<Self> {
+        [
+            KemId::MlKem512,
+            KemId::MlKem768,
+            KemId::MlKem1024,
+            KemId::XWing,
+        ]
+        .into_iter()
+        .find(|kem| kem.id() == id)
+    }
+}
+
+/// Key derivation functions for HPKE
+///
+/// Optional support for `serde` if that feature is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum KdfId {
+    /// HKDF with SHA-256
+    HkdfSha256,
+    /// HKDF with SHA-384
+    HkdfSha384,
+}
+
+/// Run `$f` with the HKDF type of `$kdf` as `$hkdf`
+macro_rules! with_hkdf {
+    ($kdf: expr, $hkdf: ident => $f: expr) => {
+        match $kdf {
+            KdfId::HkdfSha256 => {
+                type $hkdf = hkdf::Hkdf<Sha256>;
+                $f
+            }
+            KdfId::HkdfSha384 => {
+                type $hkdf = hkdf::Hkdf<Sha384>;
+                $f
+            }
+        }
+    };
+}
+
+impl KdfId {
+    /// Returns the HPKE identifier of the KDF
+    pub fn id(self) -> u16 {
+        match self {
+            KdfId::HkdfSha256 => 0x0001,
+            KdfId::HkdfSha384 => 0x0002,
+        }
+    }
+
+    /// Look up a KDF by its HPKE identifier
+    pub fn from_id(id: u16) -> Option<Self> {
+        [KdfId::HkdfSha256, KdfId::HkdfSha384]
+            .into_iter()
+            .find(|kdf| kdf.id() == id)
+    }
+
+    /// Returns the output length of the hash function
+    fn length_hash(self) -> usize {
+        match self {
+            KdfId::HkdfSha256 => 32,
+            KdfId::HkdfSha384 => 48,
+        }
+    }
+
+    /// HKDF-Extract of the concatenation of `ikm`
+    fn extract(self, salt: &[u8], ikm: &[&[u8]]) -> Secret {
+        with_hkdf!(self, H => {
+            let mut extract = hkdf::HkdfExtract::<<H as HkdfHash>::Hash>::new(Some(salt));
+            for part in ikm {
+                extract.input_ikm(part);
+            }
+            Secret(extract.finalize().0.to_vec())
+        })
+    }
+
+    /// HKDF-Expand with the concatenation of `info`
+    ///
+    /// `prk` must be an extracted secret, and `length` at most `255 * Nh`.
+    fn expand(self, prk: &Secret, info: &[&[u8]], length: usize) -> Secret {
+        let mut okm = Secret(alloc::vec![0; length]);
+        with_hkdf!(self, H => {
+            H::from_prk(&prk.0)
+                .expect("pseudorandom keys are extracted")
+                .expand_multi_info(info, &mut okm.0)
+                .expect("the output length is checked")
+        });
+        okm
+    }
+}
+
+/// Names the hash function of an HKDF type
+trait HkdfHash {
+    type Hash;
+}
+
+impl HkdfHash for hkdf::Hkdf<Sha256> {
+    type Hash = Sha256;
+}
+
+impl HkdfHash for hkdf::Hkdf<Sha384> {
+    type Hash = Sha384;
+}
+
+/// AEADs for HPKE
+///
+/// Optional support for `serde` if that feature is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AeadId {
+    /// AES-128-GCM
+    Aes128Gcm,
+    /// AES-256-GCM
+    Aes256Gcm,
+    /// ChaCha20-Poly1305
+    ChaCha20Poly1305,
+}
+
+impl AeadId {
+    /// Returns the HPKE identifier of the AEAD
+    pub fn id(self) -> u16 {
+        match self {
+            AeadId::Aes128Gcm => 0x0001,
+            AeadId::Aes256Gcm => 0x0002,
+            AeadId::ChaCha20Poly1305 => 0x0003,
+        }
+    }
+
+    /// Look up an AEAD by its HPKE identifier
+    pub fn from_id(id: u16) -> Option<Self> {
+        [
+            AeadId::Aes128Gcm,
+            AeadId::Aes256Gcm,
+            AeadId::ChaCha20Poly1305,
+        ]
+        .into_iter()
+        .find(|aead| aead.id() == id)
+    }
+
+    /// Returns the key length
+    fn length_key(self) -> usize {
+        match self {
+            AeadId::Aes128Gcm => 16,
+            AeadId::Aes256Gcm | AeadId::ChaCha20Poly1305 => 32,
+        }
+    }
+
+    fn seal(self, key: &Secret, nonce: &[u8], aad: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            AeadId::Aes128Gcm => seal::<Aes128Gcm>(key, nonce, aad, msg),
+            AeadId::Aes256Gcm => seal::<Aes256Gcm>(key, nonce, aad, msg),
+            AeadId::ChaCha20Poly1305 => seal::<ChaCha20Poly1305>(key, nonce, aad, msg),
+        }
+    }
+
+    fn open(self, key: &Secret, nonce: &[u8], aad: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            AeadId::Aes128Gcm => open::<Aes128Gcm>(key, nonce, aad, msg),
+            AeadId::Aes256Gcm => open::<Aes256Gcm>(key, nonce, aad, msg),
+            AeadId::ChaCha20Poly1305 => open::<ChaCha20Poly1305>(key, nonce, aad, msg),
+        }
+    }
+}
+
+fn seal<A: Aead + KeyInit>(key: &Secret, nonce: &[u8], aad: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+    let cipher = A::new_from_slice(&key.0).expect("keys have the length of the AEAD");
+    cipher
+        .encrypt(GenericArray::from_slice(nonce), Payload { msg, aad })
+        .map_err(|_| Error::Error)
+}
+
+fn open<A: Aead + KeyInit>(key: &Secret, nonce: &[u8], aad: &[u8], msg: &[u8]) -> Result<Vec<u8>> {
+    let cipher = A::new_from_slice(&key.0).expect("keys have the length of the AEAD");
+    cipher
+        .decrypt(GenericArray::from_slice(nonce), Payload { msg, aad })
+        .map_err(|_| Error::Error)
+}
+
+/// Intermediate secret that is wiped when dropped
+struct Secret(Vec<u8>);
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        crate::macros::cleanse(&mut self.0);
+    }
+}
+
+/// Pre-shared key for the PSK mode
+///
+/// The key must be at least 32 bytes long, the identifier must not be empty.
+#[derive(Clone, Copy)]
+pub struct Psk<'a> {
+    /// The pre-shared key
+    pub key: &'a [u8],
+    /// Identifies the pre-shared key to the recipient
+    pub id: &'a [u8],
+}
+
+impl core::fmt::Debug for Psk<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Psk")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The key schedule of a cipher suite
+struct KeySchedule {
+    suite_id: [u8; 10],
+    kdf: KdfId,
+    aead: AeadId,
+}
+
+impl KeySchedule {
+    fn new(kem_id: u16, kdf: KdfId, aead: AeadId) -> Self {
+        let mut suite_id = *b"HPKE\0\0\0\0\0\0";
+        suite_id[4..6].copy_from_slice(&kem_id.to_be_bytes());
+        suite_id[6..8].copy_from_slice(&kdf.id().to_be_bytes());
+        suite_id[8..].copy_from_slice(&aead.id().to_be_bytes());
+        Self {
+            suite_id,
+            kdf,
+            aead,
+        }
+    }
+
+    fn labeled_extract(&self, salt: &[u8], label: &[u8], ikm: &[u8]) -> Secret {
+        self.kdf
+            .extract(salt, &[b"HPKE-v1", &self.suite_id, label, ikm])
+    }
+
+    fn labeled_expand(
+        &self,
+        prk: &Secret,
+        label: &[u8],
+        info: &[u8],
+        length: usize,
+    ) -> Result<Secret> {
+        // the limit of HKDF, which also fits the two length bytes
+        let max = 255 * self.kdf.length_hash();
+        if length > max {
+            return Err(Error::WrongLength {
+                operation: Operation::KeySchedule,
+                algorithm: HPKE,
+                expected: max,
+                actual: length,
+            });
+        }
+        let length_bytes = (length as u16).to_be_bytes();
+        Ok(self.kdf.expand(
+            prk,
+            &[&length_bytes, b"HPKE-v1", &self.suite_id, label, info],
+            length,
+        ))
+    }
+
+    /// Derive the encryption context from the KEM shared secret
+    fn context(&self, shared_secret: &[u8], info: &[u8], psk: Option<Psk>) -> Result<Context> {
+        let (mode, psk) = match psk {
+            None => (MODE_BASE, Psk { key: &[], id: &[] }),
+            Some(psk) if psk.key.len() < 32 || psk.id.is_empty() => {
+                // the key is too short, or the identifier empty
+                let (expected, actual) = if psk.key.len() < 32 {
+                    (32, psk.key.len())
+                } else {
+                    (1, 0)
+                };
+                return Err(Error::WrongLength {
+                    operation: Operation::KeySchedule,
+                    algorithm: HPKE,
+                    expected,
+                    actual,
+                });
+            }
+            Some(psk) => (MODE_PSK, psk),
+        };
+        let psk_id_hash = self.labeled_extract(&[], b"psk_id_hash", psk.id);
+        let info_hash = self.labeled_extract(&[], b"info_hash", info);
+        let key_schedule_context = [&[mode], &psk_id_hash.0[..], &info_hash.0].concat();
+
+        let secret = self.labeled_extract(shared_secret, b"secret", psk.key);
+        let key = self.labeled_expand(
+            &secret,
+            b"key",
+            &key_schedule_context,
+            self.aead.length_key(),
+        )?;
+        let base_nonce =
+            self.labeled_expand(&secret, b"base_nonce", &key_schedule_context, NONCE_LENGTH)?;
+        let exporter_secret = self.labeled_expand(
+            &secret,
+            b"exp",
+            &key_schedule_context,
+            self.kdf.length_hash(),
+        )?;
+        Ok(Context {
+            schedule: KeySchedule { ..*self },
+            key,
+            base_nonce,
+            exporter_secret,
+            seq: 0,
+        })
+    }
+}
+
+/// Encryption context of a sender or a recipient
+///
+/// Each call to [`seal`](Context::seal) or [`open`](Context::open) uses the next nonce,
+/// so the recipient must open the messages in the order in which they were sealed.
+pub struct Context {
+    schedule: KeySchedule,
+    key: Secret,
+    base_nonce: Secret,
+    exporter_secret: Secret,
+    seq: u64,
+}
+
+impl Context {
+    /// Encrypt `plaintext` and authenticate it together with `aad`
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        let ciphertext = self
+            .schedule
+            .aead
+            .seal(&self.key, &self.nonce(), aad, plaintext)?;
+        self.increment_seq()?;
+        Ok(ciphertext)
+    }
+
+    /// Decrypt `ciphertext` and check it and `aad`
+    ///
+    /// Returns [`Error::Error`] if the ciphertext is not authentic.
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let plaintext = self
+            .schedule
+            .aead
+            .open(&self.key, &self.nonce(), aad, ciphertext)?;
+        self.increment_seq()?;
+        Ok(plaintext)
+    }
+
+    /// Derive a secret of `length` bytes for `exporter_context`
+    pub fn export(&self, exporter_context: &[u8], length: usize) -> Result<Vec<u8>> {
+        let mut secret = self.schedule.labeled_expand(
+            &self.exporter_secret,
+            b"sec",
+            exporter_context,
+            length,
+        )?;
+        Ok(core::mem::take(&mut secret.0))
+    }
+
+    fn nonce(&self) -> [u8; NONCE_LENGTH] {
+        let mut nonce = [0; NONCE_LENGTH];
+        nonce[NONCE_LENGTH - 8..].copy_from_slice(&self.seq.to_be_bytes());
+        for (n, b) in nonce.iter_mut().zip(&self.base_nonce.0) {
+            *n ^= b;
+        }
+        nonce
+    }
+
+    fn increment_seq(&mut self) -> Result<()> {
+        self.seq = self.seq.checked_add(1).ok_or(Error::Error)?;
+        Ok(())
+    }
+}
+
+enum HpkeKem {
+    MlKem(Kem),
+    XWing(XWing),
+}
+
+/// HPKE with a post-quantum KEM
+///
+/// # Example
+/// ```rust
+/// # if !cfg!(feature = "ml_kem") { return; }
+/// use oqs::hpke::{AeadId, Hpke, KdfId, KemId};
+/// oqs::init();
+/// let hpke = Hpke::new(KemId::MlKem768, KdfId::HkdfSha256, AeadId::Aes128Gcm).unwrap();
+/// let (pk, sk) = hpke.keypair().unwrap();
+/// let (enc, ct) = hpke.seal(&pk, b"info", b"aad", b"message").unwrap();
+/// let pt = hpke.open(&enc, &sk, b"info", b"aad", &ct).unwrap();
+/// assert_eq!(pt, b"message");
+/// ```
+pub struct Hpke {
+    kem_id: KemId,
+    kem: HpkeKem,
+    schedule: KeySchedule,
+}
+
+impl Hpke {
+    /// Construct the cipher suite of `kem`, `kdf` and `aead`
+    pub fn new(kem: KemId, kdf: KdfId, aead: AeadId) -> Result<Self> {
+        let kem_impl = match kem {
+            KemId::MlKem512 => HpkeKem::MlKem(Kem::new(kem::Algorithm::MlKem512)?),
+            KemId::MlKem768 => HpkeKem::MlKem(Kem::new(kem::Algorithm::MlKem768)?),
+            KemId::MlKem1024 => HpkeKem::MlKem(Kem::new(kem::Algorithm::MlKem1024)?),
+            KemId::XWing => HpkeKem::XWing(XWing::new()?),
+        };
+        Ok(Self {
+            kem_id: kem,
+            kem: kem_impl,
+            schedule: KeySchedule::new(kem.id(), kdf, aead),
+        })
+    }
+
+    /// Get the KEM of this suite
+    pub fn kem(&self) -> KemId {
+        self.kem_id
+    }
+
+    /// Get the KDF of this suite
+    pub fn kdf(&self) -> KdfId {
+        self.schedule.kdf
+    }
+
+    /// Get the AEAD of this suite
+    pub fn aead(&self) -> AeadId {
+        self.schedule.aead
+    }
+
+    /// Name of the KEM in errors
+    fn name(&self) -> &'static str {
+        match &self.kem {
+            HpkeKem::MlKem(kem) => kem.algorithm().name(),
+            HpkeKem::XWing(_) => crate::hybrid::XWING,
+        }
+    }
+
+    /// Get the length of the public key
+    pub fn length_public_key(&self) -> usize {
+        match &self.kem {
+            HpkeKem::MlKem(kem) => kem.length_public_key(),
+            HpkeKem::XWing(xwing) => xwing.length_public_key(),
+        }
+    }
+
+    /// Get the length of the secret key
+    ///
+    /// Secret keys are the seeds of the KEM key generation:
+    /// `d || z` (64 bytes) for ML-KEM and the 32-byte secret key for X-Wing.
+    pub fn length_secret_key(&self) -> usize {
+        match &self.kem {
+            HpkeKem::MlKem(kem) => kem.length_keypair_seed(),
+            HpkeKem::XWing(xwing) => xwing.length_secret_key(),
+        }
+    }
+
+    /// Get the length of the encapsulated key
+    pub fn length_encapsulated_key(&self) -> usize {
+        match &self.kem {
+            HpkeKem::MlKem(kem) => kem.length_ciphertext(),
+            HpkeKem::XWing(xwing) => xwing.length_ciphertext(),
+        }
+    }
+
+    /// Obtain a secret key objects from bytes
+    ///
+    /// Returns None if the secret key is not the correct length.
+    pub fn secret_key_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<SecretKeyRef<'a>> {
+        if self.length_secret_key() != buf.len() {
+            None
+        } else {
+            Some(SecretKeyRef::new(buf))
+        }
+    }
+
+    /// Obtain a public key from bytes
+    ///
+    /// Returns None if the public key is not the correct length.
+    pub fn public_key_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<PublicKeyRef<'a>> {
+        if self.length_public_key() != buf.len() {
+            None
+        } else {
+            Some(PublicKeyRef::new(buf))
+        }
+    }
+
+    /// Obtain an encapsulated key from bytes
+    ///
+    /// Returns None if the encapsulated key is not the correct length.
+    pub fn encapsulated_key_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<EncapsulatedKeyRef<'a>> {
+        if self.length_encapsulated_key() != buf.len() {
+            None
+        } else {
+            Some(EncapsulatedKeyRef::new(buf))
+        }
+    }
+
+    /// Generate a new keypair
+    pub fn keypair(&self) -> Result<(PublicKey, SecretKey)> {
+        let mut sk = SecretKey {
+            bytes: alloc::vec![0; self.length_secret_key()],
+        };
+        crate::rand::randombytes(&mut sk.bytes);
+        let pk = self.public_key(&sk)?;
+        Ok((pk, sk))
+    }
+
+    /// Derive a keypair from the input keying material `ikm`
+    ///
+    /// This is `DeriveKeyPair` of the draft: the secret key is
+    /// `SHAKE256.LabeledDerive(ikm, "DeriveKeyPair", "", Nsk)`.
+    pub fn derive_keypair(&self, ikm: &[u8]) -> Result<(PublicKey, SecretKey)> {
+        let length = self.length_secret_key();
+        let label = b"DeriveKeyPair";
+        let mut suite_id = *b"KEM\0\0";
+        suite_id[3..].copy_from_slice(&self.kem_id.id().to_be_bytes());
+        let mut shake = Shake256::default();
+        for part in [
+            ikm,
+            b"HPKE-v1",
+            &suite_id,
+            &(label.len() as u16).to_be_bytes(),
+            label,
+            &(length as u16).to_be_bytes(),
+        ] {
+            shake.update(part);
+        }
+        let mut sk = SecretKey {
+            bytes: alloc::vec![0; length],
+        };
+        shake.finalize_xof_into(&mut sk.bytes);
+        let pk = self.public_key(&sk)?;
+        Ok((pk, sk))
+    }
+
+    /// Compute the public key that belongs to `sk`
+    pub fn public_key<'a>(&self, sk: impl Into<SecretKeyRef<'a>>) -> Result<PublicKey> {
+        let sk = sk.into();
+        let operation = Operation::Keypair;
+        check_length(operation, self.name(), self.length_secret_key(), sk.len())?;
+        let pk = match &self.kem {
+            HpkeKem::MlKem(kem) => expand(kem, &sk)?.0.into_vec(),
+            HpkeKem::XWing(xwing) => {
+                let sk = xwing.secret_key_from_bytes(&sk).expect("length is checked");
+                xwing.public_key(sk)?.into_vec()
+            }
+        };
+        Ok(PublicKey { bytes: pk })
+    }
+
+    /// Set up a context to encrypt to the owner of `pk`
+    pub fn setup_sender<'a>(
+        &self,
+        pk: impl Into<PublicKeyRef<'a>>,
+        info: &[u8],
+    ) -> Result<(EncapsulatedKey, Context)> {
+        self.sender(pk.into(), info, None)
+    }
+
+    /// Set up a context to encrypt to the owner of `pk` who also knows `psk`
+    pub fn setup_sender_psk<'a>(
+        &self,
+        pk: impl Into<PublicKeyRef<'a>>,
+        info: &[u8],
+        psk: Psk,
+    ) -> Result<(EncapsulatedKey, Context)> {
+        self.sender(pk.into(), info, Some(psk))
+    }
+
+    /// Set up a context to decrypt the messages sent with `enc`
+    pub fn setup_receiver<'a, 'b>(
+        &self,
+        enc: impl Into<EncapsulatedKeyRef<'a>>,
+        sk: impl Into<SecretKeyRef<'b>>,
+        info: &[u8],
+    ) -> Result<Context> {
+        self.receiver(enc.into(), sk.into(), info, None)
+    }
+
+    /// Set up a context to decrypt the messages sent with `enc` and `psk`
+    pub fn setup_receiver_psk<'a, 'b>(
+        &self,
+        enc: impl Into<EncapsulatedKeyRef<'a>>,
+        sk: impl Into<SecretKeyRef<'b>>,
+        info: &[u8],
+        psk: Psk,
+    ) -> Result<Context> {
+        self.receiver(enc.into(), sk.into(), info, Some(psk))
+    }
+
+    /// Encrypt a single message to the owner of `pk`
+    pub fn seal<'a>(
+        &self,
+        pk: impl Into<PublicKeyRef<'a>>,
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<(EncapsulatedKey, Vec<u8>)> {
+        let (enc, mut context) = self.setup_sender(pk, info)?;
+        Ok((enc, context.seal(aad, plaintext)?))
+    }
+
+    /// Decrypt a single message
+    pub fn open<'a, 'b>(
+        &self,
+        enc: impl Into<EncapsulatedKeyRef<'a>>,
+        sk: impl Into<SecretKeyRef<'b>>,
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        self.setup_receiver(enc, sk, info)?.open(aad, ciphertext)
+    }
+
+    /// Encrypt a single message to the owner of `pk` who also knows `psk`
+    pub fn seal_psk<'a>(
+        &self,
+        pk: impl Into<PublicKeyRef<'a>>,
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+        psk: Psk,
+    ) -> Result<(EncapsulatedKey, Vec<u8>)> {
+        let (enc, mut context) = self.setup_sender_psk(pk, info, psk)?;
+        Ok((enc, context.seal(aad, plaintext)?))
+    }
+
+    /// Decrypt a single message sent with `psk`
+    pub fn open_psk<'a, 'b>(
+        &self,
+        enc: impl Into<EncapsulatedKeyRef<'a>>,
+        sk: impl Into<SecretKeyRef<'b>>,
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+        psk: Psk,
+    ) -> Result<Vec<u8>> {
+        self.setup_receiver_psk(enc, sk, info, psk)?
+            .open(aad, ciphertext)
+    }
+
+    fn sender(
+        &self,
+        pk: PublicKeyRef,
+        info: &[u8],
+        psk: Option<Psk>,
+    ) -> Result<(EncapsulatedKey, Context)> {
+        let operation = Operation::Encapsulate;
+        check_length(operation, self.name(), self.length_public_key(), pk.len())?;
+        let (enc, shared_secret) = match &self.kem {
+            HpkeKem::MlKem(kem) => {
+                let pk = kem.public_key_from_bytes(&pk).expect("length is checked");
+                let (ct, ss) = kem.encapsulate(pk)?;
+                (ct.into_vec(), Secret(ss.into_vec()))
+            }
+            HpkeKem::XWing(xwing) => {
+                let pk = xwing.public_key_from_bytes(&pk).expect("length is checked");
+                let (ct, ss) = xwing.encapsulate(pk)?;
+                (ct.into_vec(), Secret(ss.into_vec()))
+            }
+        };
+        let context = self.schedule.context(&shared_secret.0, info, psk)?;
+        Ok((EncapsulatedKey { bytes: enc }, context))
+    }
+
+    fn receiver(
+        &self,
+        enc: EncapsulatedKeyRef,
+        sk: SecretKeyRef,
+        info: &[u8],
+        psk: Option<Psk>,
+    ) -> Result<Context> {
+        let operation = Operation::Decapsulate;
+        check_length(operation, self.name(), self.length_secret_key(), sk.len())?;
+        check_length(
+            operation,
+            self.name(),
+            self.length_encapsulated_key(),
+            enc.len(),
+        )?;
+        let shared_secret = match &self.kem {
+            HpkeKem::MlKem(kem) => {
+                let (_, sk) = expand(kem, &sk)?;
+                let ct = kem.ciphertext_from_bytes(&enc).expect("length is checked");
+                Secret(kem.decapsulate(&sk, ct)?.into_vec())
+            }
+            HpkeKem::XWing(xwing) => {
+                let sk = xwing.secret_key_from_bytes(&sk).expect("length is checked");
+                let ct = xwing
+                    .ciphertext_from_bytes(&enc)
+                    .expect("length is checked");
+                Secret(xwing.decapsulate(sk, ct)?.into_vec())
+            }
+        };
+        self.schedule.context(&shared_secret.0, info, psk)
+    }
+}
+
+/// Expand an ML-KEM secret key, which is the seed `d || z`, into the keypair
+///
+/// The caller checks the length of `sk`.
+fn expand(kem: &Kem, sk: &[u8]) -> Result<(kem::PublicKey, kem::SecretKey)> {
+    let seed = kem.keypair_seed_from_bytes(sk).expect("length is checked");
+    kem.keypair_derand(seed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Parse the `key = value` records of `testdata/hpke.txt`
+    fn vectors() -> Vec<Vec<(&'static str, &'static str)>> {
+        include_str!("../testdata/hpke.txt")
+            .split("\n\n")
+            .map(|record| {
+                record
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .filter_map(|line| line.split_once('='))
+                    .map(|(key, value)| (key.trim(), value.trim()))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|record| !record.is_empty())
+            .collect()
+    }
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Check a context against a test vector, both as sender and as recipient
+    fn check_context(
+        get: &dyn Fn(&str) -> Vec<u8>,
+        mut sender: Context,
+        mut receiver: Context,
+    ) -> Result<()> {
+        assert_eq!(sender.key.0, get("key"));
+        assert_eq!(sender.base_nonce.0, get("base_nonce"));
+        assert_eq!(sender.exporter_secret.0, get("exporter_secret"));
+        for seq in ["0", "1"] {
+            let aad = get(&["aad", seq].concat());
+            let ct = get(&["ct", seq].concat());
+            assert_eq!(sender.seal(&aad, &get("pt"))?, ct);
+            assert_eq!(receiver.open(&aad, &ct)?, get("pt"));
+        }
+        let export = get("export");
+        assert_eq!(
+            receiver.export(&get("exporter_context"), export.len())?,
+            export
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_schedule() -> Result<()> {
+        let vectors = vectors();
+        // the RFC 9180 vector has a classical KEM, which we only use for the key schedule
+        let record = vectors
+            .iter()
+            .find(|record| record.contains(&("kem_id", "32")))
+            .unwrap();
+        let get = |key: &str| unhex(record.iter().find(|(k, _)| *k == key).unwrap().1);
+        let schedule = KeySchedule::new(0x0020, KdfId::HkdfSha256, AeadId::Aes128Gcm);
+        let context = || schedule.context(&get("shared_secret"), &get("info"), None);
+        check_context(&get, context()?, context()?)
+    }
+
+    #[test]
+    #[cfg(feature = "ml_kem")]
+    fn test_vectors() -> Result<()> {
+        let _lock = crate::rand::test_lock();
+        crate::init();
+        let vectors = vectors();
+        let records = vectors
+            .iter()
+            .filter(|record| !record.contains(&("kem_id", "32")));
+        let mut kems = Vec::new();
+        for record in records {
+            let field = |key: &str| record.iter().find(|(k, _)| *k == key).unwrap().1;
+            let get = |key: &str| unhex(field(key));
+            let id = |key: &str| field(key).parse::<u16>().unwrap();
+            let kem = KemId::from_id(id("kem_id")).unwrap();
+            let kdf = KdfId::from_id(id("kdf_id")).unwrap();
+            let aead = AeadId::from_id(id("aead_id")).unwrap();
+            let hpke = Hpke::new(kem, kdf, aead)?;
+            kems.push(kem);
+
+            let (pk, sk) = hpke.derive_keypair(&get("ikm"))?;
+            assert_eq!(sk.as_ref(), get("sk"));
+            assert_eq!(pk.as_ref(), get("pk"));
+
+            let (psk, psk_id) = (get("psk"), get("psk_id"));
+            let psk = (field("mode") == "1").then_some(Psk {
+                key: &psk,
+                id: &psk_id,
+            });
+            let info = get("info");
+            let (enc, sender) = crate::rand::with_fixed_bytes(&get("eseed"), || {
+                hpke.sender((&pk).into(), &info, psk)
+            })??;
+            assert_eq!(enc.as_ref(), get("enc"));
+            let receiver = hpke.receiver((&enc).into(), (&sk).into(), &info, psk)?;
+            check_context(&get, sender, receiver)?;
+
+            // the shared secret determines the context
+            let context = hpke.schedule.context(&get("shared_secret"), &info, psk)?;
+            assert_eq!(context.key.0, get("key"));
+        }
+        for kem in [KemId::MlKem768, KemId::MlKem1024, KemId::XWing] {
+            assert!(kems.contains(&kem));
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ml_kem")]
+    fn test_derive_keypair() -> Result<()> {
+        crate::init();
+        for (kem, length) in [
+            (KemId::MlKem512, 64),
+            (KemId::MlKem768, 64),
+            (KemId::MlKem1024, 64),
+            (KemId::XWing, 32),
+        ] {
+            let hpke = Hpke::new(kem, KdfId::HkdfSha256, AeadId::Aes128Gcm)?;
+            let (pk, sk) = hpke.derive_keypair(b"input keying material")?;
+            assert_eq!(sk.len(), length);
+            assert_eq!(
+                hpke.derive_keypair(b"input keying material")?,
+                (pk.clone(), sk.clone())
+            );
+            assert_ne!(hpke.derive_keypair(b"other keying material")?.1, sk);
+            assert_eq!(hpke.public_key(&sk)?, pk);
+
+            // the secret key is the seed of the KEM key generation
+            match &hpke.kem {
+                HpkeKem::MlKem(kem) => {
+                    let seed = kem.keypair_seed_from_bytes(sk.as_ref()).unwrap();
+                    assert_eq!(kem.keypair_derand(seed)?.0.as_ref(), pk.as_ref());
+                }
+                HpkeKem::XWing(xwing) => {
+                    assert_eq!(xwing.keypair_derand(sk.as_ref())?.0.as_ref(), pk.as_ref());
+                }
+            }
+
+            let (enc, ct) = hpke.seal(&pk, b"info", b"aad", b"message")?;
+            assert_eq!(hpke.open(&enc, &sk, b"info", b"aad", &ct)?, b"message");
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ml_kem")]
+    fn test_seal_open() -> Result<()> {
+        crate::init();
+        let psk = Psk {
+            key: &[7; 32],
+            id: b"psk id",
+        };
+        for kem in [
+            KemId::MlKem512,
+            KemId::MlKem768,
+            KemId::MlKem1024,
+            KemId::XWing,
+        ] {
+            for kdf in [KdfId::HkdfSha256, KdfId::HkdfSha384] {
+                for aead in [
+                    AeadId::Aes128Gcm,
+                    AeadId::Aes256Gcm,
+                    AeadId::ChaCha20Poly1305,
+                ] {
+                    let hpke = Hpke::new(kem, kdf, aead)?;
+                    let (pk, sk) = hpke.keypair()?;
+                    let (enc, ct) = hpke.seal(&pk, b"info", b"aad", b"message")?;
+                    assert_eq!(hpke.open(&enc, &sk, b"info", b"aad", &ct)?, b"message");
+                    assert!(hpke.open(&enc, &sk, b"other", b"aad", &ct).is_err());
+                    assert!(hpke.open(&enc, &sk, b"info", b"other", &ct).is_err());
+                    assert!(hpke.open_psk(&enc, &sk, b"info", b"aad", &ct, psk).is_err());
+
+                    let (enc, ct) = hpke.seal_psk(&pk, b"info", b"aad", b"message", psk)?;
+                    let pt = hpke.open_psk(&enc, &sk, b"info", b"aad", &ct, psk)?;
+                    assert_eq!(pt, b"message");
+                    assert!(hpke.open(&enc, &sk, b"info", b"aad", &ct).is_err());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ml_kem")]
+    fn test_invalid_psk() -> Result<()> {
+        crate::init();
+        let hpke = Hpke::new(KemId::MlKem768, KdfId::HkdfSha256, AeadId::Aes128Gcm)?;
+        let (pk, _) = hpke.keypair()?;
+        let short = Psk {
+            key: &[7; 31],
+            id: b"psk id",
+        };
+        assert!(matches!(
+            hpke.setup_sender_psk(&pk, b"", short),
+            Err(Error::WrongLength {
+                operation: Operation::KeySchedule,
+                expected: 32,
+                actual: 31,
+                ..
+            })
+        ));
+        let no_id = Psk {
+            key: &[7; 32],
+            id: b"",
+        };
+        assert!(matches!(
+            hpke.setup_sender_psk(&pk, b"", no_id),
+            Err(Error::WrongLength {
+                expected: 1,
+                actual: 0,
+                ..
+            })
+        ));
+        Ok(())
+    }
+}
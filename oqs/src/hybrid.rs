@@ -0,0 +1,708 @@
+//! Hybrid KEMs
+//!
+//! A hybrid KEM combines a post-quantum KEM from liboqs with classical
+//! elliptic-curve Diffie-Hellman. The shared secret stays secure as long
+//! as either of the two components is not broken.
+//!
+//! [`XWing`] implements X-Wing, which pairs ML-KEM-768 with X25519 as specified in
+//! [draft-connolly-cfrg-xwing-kem](https://datatracker.ietf.org/doc/draft-connolly-cfrg-xwing-kem/).
+//! [`HybridKem`] is a generic combiner for any [`kem::Algorithm`] and [`Group`].
+//!
+//! Both draw their randomness from the generator liboqs uses, see [`crate::rand`].
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha3::digest::{Digest, ExtendableOutput};
+use sha3::{Sha3_256, Shake256};
+
+use crate::kem::{self, Kem};
+use crate::newtype_buffer;
+use crate::*;
+
+newtype_buffer!(PublicKey, PublicKeyRef);
+newtype_buffer!(secret SecretKey, SecretKeyRef);
+newtype_buffer!(Ciphertext, CiphertextRef);
+newtype_buffer!(secret SharedSecret, SharedSecretRef);
+
+/// Hash the concatenation of `parts` into a shared secret
+fn combine(parts: &[&[u8]]) -> SharedSecret {
+    let mut hash = Sha3_256::new();
+    for part in parts {
+        Digest::update(&mut hash, part);
+    }
+    SharedSecret {
+        bytes: hash.finalize().to_vec(),
+    }
+}
+
+/// Elliptic-curve groups for [`HybridKem`]
+///
+/// Optional support for `serde` if that feature is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Group {
+    /// Diffie-Hellman over Curve25519 as in RFC 7748
+    X25519,
+    /// ECDH over NIST P-256 with uncompressed points
+    P256,
+    /// ECDH over NIST P-384 with uncompressed points
+    P384,
+}
+
+impl Group {
+    /// Returns the name of the group
+    pub fn name(self) -> &'static str {
+        match self {
+            Group::X25519 => "X25519",
+            Group::P256 => "P-256",
+            Group::P384 => "P-384",
+        }
+    }
+
+    /// Get the length of a public key, which is also the length of a ciphertext
+    pub fn length_public_key(self) -> usize {
+        match self {
+            Group::X25519 => 32,
+            Group::P256 => 65,
+            Group::P384 => 97,
+        }
+    }
+
+    /// Get the length of a secret key
+    pub fn length_secret_key(self) -> usize {
+        match self {
+            Group::X25519 | Group::P256 => 32,
+            Group::P384 => 48,
+        }
+    }
+
+    /// Generate a new keypair from the liboqs generator
+    fn keypair(self) -> (Vec<u8>, SecretKey) {
+        let mut sk = SecretKey {
+            bytes: alloc::vec![0; self.length_secret_key()],
+        };
+        loop {
+            crate::rand::randombytes(&mut sk.bytes);
+            // only fails for scalars out of range on the NIST curves
+            if let Ok(pk) = self.public_key(&sk.bytes) {
+                return (pk, sk);
+            }
+        }
+    }
+
+    fn public_key(self, sk: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Group::X25519 => {
+                let sk = x25519_secret(Operation::Keypair, sk)?;
+                Ok(x25519_dalek::PublicKey::from(&sk).as_bytes().to_vec())
+            }
+            Group::P256 => nist::public_key::<p256::NistP256>(sk),
+            Group::P384 => nist::public_key::<p384::NistP384>(sk),
+        }
+    }
+
+    fn diffie_hellman(self, operation: Operation, sk: &[u8], pk: &[u8]) -> Result<SharedSecret> {
+        check_length(operation, self.name(), self.length_public_key(), pk.len())?;
+        let bytes = match self {
+            Group::X25519 => {
+                let sk = x25519_secret(operation, sk)?;
+                let ss = sk.diffie_hellman(&x25519_public(operation, pk)?);
+                // reject low-order points
+                if !ss.was_contributory() {
+                    return Err(Error::Error);
+                }
+                ss.as_bytes().to_vec()
+            }
+            Group::P256 => nist::diffie_hellman::<p256::NistP256>(sk, pk)?,
+            Group::P384 => nist::diffie_hellman::<p384::NistP384>(sk, pk)?,
+        };
+        Ok(SharedSecret { bytes })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for Group {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+fn x25519_secret(operation: Operation, sk: &[u8]) -> Result<x25519_dalek::StaticSecret> {
+    Ok(x25519_dalek::StaticSecret::from(x25519_bytes(
+        operation, sk,
+    )?))
+}
+
+fn x25519_public(operation: Operation, pk: &[u8]) -> Result<x25519_dalek::PublicKey> {
+    Ok(x25519_dalek::PublicKey::from(x25519_bytes(operation, pk)?))
+}
+
+fn x25519_bytes(operation: Operation, bytes: &[u8]) -> Result<[u8; 32]> {
+    bytes.try_into().map_err(|_| Error::WrongLength {
+        operation,
+        algorithm: Group::X25519.name(),
+        expected: 32,
+        actual: bytes.len(),
+    })
+}
+
+/// ECDH over the NIST curves
+mod nist {
+    use alloc::vec::Vec;
+
+    use p256::elliptic_curve::ecdh::diffie_hellman as ecdh;
+    use p256::elliptic_curve::sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint};
+    use p256::elliptic_curve::{
+        AffinePoint, CurveArithmetic, FieldBytesSize, PublicKey, SecretKey,
+    };
+
+    use crate::{Error, Result};
+
+    pub(super) fn public_key<C>(sk: &[u8]) -> Result<Vec<u8>>
+    where
+        C: CurveArithmetic,
+        FieldBytesSize<C>: ModulusSize,
+        AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    {
+        let sk = SecretKey::<C>::from_slice(sk).map_err(|_| Error::Error)?;
+        Ok(sk.public_key().to_encoded_point(false).as_bytes().to_vec())
+    }
+
+    pub(super) fn diffie_hellman<C>(sk: &[u8], pk: &[u8]) -> Result<Vec<u8>>
+    where
+        C: CurveArithmetic,
+        FieldBytesSize<C>: ModulusSize,
+        AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    {
+        let sk = SecretKey::<C>::from_slice(sk).map_err(|_| Error::Error)?;
+        let pk = PublicKey::<C>::from_sec1_bytes(pk).map_err(|_| Error::Error)?;
+        let ss = ecdh(sk.to_nonzero_scalar(), pk.as_affine());
+        Ok(ss.raw_secret_bytes().to_vec())
+    }
+}
+
+macro_rules! from_bytes {
+    () => {
+        /// Obtain a secret key objects from bytes
+        ///
+        /// Returns None if the secret key is not the correct length.
+        pub fn secret_key_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<SecretKeyRef<'a>> {
+            if self.length_secret_key() != buf.len() {
+                None
+            } else {
+                Some(SecretKeyRef::new(buf))
+            }
+        }
+
+        /// Obtain a public key from bytes
+        ///
+        /// Returns None if the public key is not the correct length.
+        pub fn public_key_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<PublicKeyRef<'a>> {
+            if self.length_public_key() != buf.len() {
+                None
+            } else {
+                Some(PublicKeyRef::new(buf))
+            }
+        }
+
+        /// Obtain a ciphertext from bytes
+        ///
+        /// Returns None if the ciphertext is not the correct length.
+        pub fn ciphertext_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<CiphertextRef<'a>> {
+            if self.length_ciphertext() != buf.len() {
+                None
+            } else {
+                Some(CiphertextRef::new(buf))
+            }
+        }
+
+        /// Obtain a shared secret from bytes
+        ///
+        /// Returns None if the shared secret is not the correct length.
+        pub fn shared_secret_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<SharedSecretRef<'a>> {
+            if self.length_shared_secret() != buf.len() {
+                None
+            } else {
+                Some(SharedSecretRef::new(buf))
+            }
+        }
+    };
+}
+
+/// The X-Wing hybrid KEM of ML-KEM-768 and X25519
+///
+/// Secret keys are 32-byte seeds from which the ML-KEM-768 and X25519 keys are derived.
+/// Requires ML-KEM-768 to be enabled in liboqs.
+///
+/// # Example
+/// ```rust
+/// # if !cfg!(feature = "ml_kem") { return; }
+/// use oqs;
+/// oqs::init();
+/// let xwing = oqs::hybrid::XWing::new().unwrap();
+/// let (pk, sk) = xwing.keypair().unwrap();
+/// let (ct, ss) = xwing.encapsulate(&pk).unwrap();
+/// let ss2 = xwing.decapsulate(&sk, &ct).unwrap();
+/// assert_eq!(ss, ss2);
+/// ```
+pub struct XWing {
+    kem: Kem,
+}
+
+/// Final input to the X-Wing combiner, `\.//^\`
+const XWING_LABEL: &[u8] = b"\\.//^\\";
+
+/// Name of X-Wing in errors
+pub(crate) const XWING: &str = "X-Wing";
+
+impl XWing {
+    /// Construct a new X-Wing instance
+    pub fn new() -> Result<Self> {
+        let kem = Kem::new(kem::Algorithm::MlKem768)?;
+        Ok(Self { kem })
+    }
+
+    /// Get the length of the public key
+    pub fn length_public_key(&self) -> usize {
+        self.kem.length_public_key() + 32
+    }
+
+    /// Get the length of the secret key
+    pub fn length_secret_key(&self) -> usize {
+        32
+    }
+
+    /// Get the length of the ciphertext
+    pub fn length_ciphertext(&self) -> usize {
+        self.kem.length_ciphertext() + 32
+    }
+
+    /// Get the length of a shared secret
+    pub fn length_shared_secret(&self) -> usize {
+        32
+    }
+
+    from_bytes!();
+
+    /// Generate a new keypair
+    pub fn keypair(&self) -> Result<(PublicKey, SecretKey)> {
+        let mut sk = SecretKey {
+            bytes: alloc::vec![0; self.length_secret_key()],
+        };
+        crate::rand::randombytes(&mut sk.bytes);
+        let pk = self.public_key(&sk)?;
+        Ok((pk, sk))
+    }
+
+    /// Derive the keypair whose secret key is `seed`
+    pub fn keypair_derand(&self, seed: &[u8]) -> Result<(PublicKey, SecretKey)> {
+        check_length(
+            Operation::Keypair,
+            XWING,
+            self.length_secret_key(),
+            seed.len(),
+        )?;
+        let sk = SecretKey {
+            bytes: seed.to_vec(),
+        };
+        let pk = self.public_key(&sk)?;
+        Ok((pk, sk))
+    }
+
+    /// Compute the public key that belongs to `sk`
+    pub fn public_key<'a, S: Into<SecretKeyRef<'a>>>(&self, sk: S) -> Result<PublicKey> {
+        let (_, pk_m, _, pk_x) = self.expand(Operation::Keypair, sk.into())?;
+        Ok(PublicKey {
+            bytes: [pk_m.as_ref(), pk_x.as_bytes()].concat(),
+        })
+    }
+
+    /// Encapsulate to the provided public key
+    pub fn encapsulate<'a, P: Into<PublicKeyRef<'a>>>(
+        &self,
+        pk: P,
+    ) -> Result<(Ciphertext, SharedSecret)> {
+        let (pk_m, pk_x) = self.split_public_key(pk.into())?;
+        let (ct_m, ss_m) = self.kem.encapsulate(pk_m)?;
+        let (ct_x, ek_x) = Group::X25519.keypair();
+        self.finish_encapsulate(&ct_m, &ss_m, &ct_x, &ek_x.bytes, pk_x)
+    }
+
+    /// Encapsulate to the provided public key, deriving all randomness from `eseed`
+    ///
+    /// `eseed` must be 64 bytes.
+    /// This is `EncapsulateDerand` of the specification, which we only need for
+    /// the test vectors: liboqs has no derandomized ML-KEM encapsulation,
+    /// so this temporarily replaces the process-wide generator.
+    #[cfg(test)]
+    fn encapsulate_derand<'a, P: Into<PublicKeyRef<'a>>>(
+        &self,
+        pk: P,
+        eseed: &[u8],
+    ) -> Result<(Ciphertext, SharedSecret)> {
+        check_length(Operation::Encapsulate, XWING, 64, eseed.len())?;
+        let (pk_m, pk_x) = self.split_public_key(pk.into())?;
+        // ML-KEM encapsulation draws its 32-byte message from the generator
+        let (ct_m, ss_m) =
+            crate::rand::with_fixed_bytes(&eseed[..32], || self.kem.encapsulate(pk_m))??;
+        let ek_x = &eseed[32..];
+        let ct_x = Group::X25519.public_key(ek_x)?;
+        self.finish_encapsulate(&ct_m, &ss_m, &ct_x, ek_x, pk_x)
+    }
+
+    /// Decapsulate the provided ciphertext
+    pub fn decapsulate<'a, 'b, S: Into<SecretKeyRef<'a>>, C: Into<CiphertextRef<'b>>>(
+        &self,
+        sk: S,
+        ct: C,
+    ) -> Result<SharedSecret> {
+        let ct = ct.into();
+        check_length(
+            Operation::Decapsulate,
+            XWING,
+            self.length_ciphertext(),
+            ct.len(),
+        )?;
+        let (sk_m, _, sk_x, pk_x) = self.expand(Operation::Decapsulate, sk.into())?;
+        let (ct_m, ct_x) = ct.split_at(self.kem.length_ciphertext());
+        let ct_m = self
+            .kem
+            .ciphertext_from_bytes(ct_m)
+            .expect("split at the ML-KEM ciphertext length");
+        let ss_m = self.kem.decapsulate(&sk_m, ct_m)?;
+        let ss_x = sk_x.diffie_hellman(&x25519_public(Operation::Decapsulate, ct_x)?);
+        Ok(combine(&[
+            ss_m.as_ref(),
+            ss_x.as_bytes(),
+            ct_x,
+            pk_x.as_bytes(),
+            XWING_LABEL,
+        ]))
+    }
+
+    /// Derive the component keys from an X-Wing secret key
+    fn expand(
+        &self,
+        operation: Operation,
+        sk: SecretKeyRef,
+    ) -> Result<(
+        kem::SecretKey,
+        kem::PublicKey,
+        x25519_dalek::StaticSecret,
+        x25519_dalek::PublicKey,
+    )> {
+        check_length(operation, XWING, self.length_secret_key(), sk.len())?;
+        let mut expanded = SecretKey {
+            bytes: alloc::vec![0; 96],
+        };
+        Shake256::digest_xof(&*sk, &mut expanded.bytes);
+        let seed = self
+            .kem
+            .keypair_seed_from_bytes(&expanded.bytes[..64])
+            .expect("ML-KEM key generation seeds are 64 bytes");
+        let (pk_m, sk_m) = self.kem.keypair_derand(seed)?;
+        let sk_x = x25519_secret(operation, &expanded.bytes[64..])?;
+        let pk_x = x25519_dalek::PublicKey::from(&sk_x);
+        Ok((sk_m, pk_m, sk_x, pk_x))
+    }
+
+    fn split_public_key<'a>(
+        &self,
+        pk: PublicKeyRef<'a>,
+    ) -> Result<(kem::PublicKeyRef<'a>, &'a [u8])> {
+        let pk: &'a [u8] = pk.bytes;
+        check_length(
+            Operation::Encapsulate,
+            XWING,
+            self.length_public_key(),
+            pk.len(),
+        )?;
+        let (pk_m, pk_x) = pk.split_at(self.kem.length_public_key());
+        let pk_m = self
+            .kem
+            .public_key_from_bytes(pk_m)
+            .expect("split at the ML-KEM public key length");
+        Ok((pk_m, pk_x))
+    }
+
+    fn finish_encapsulate(
+        &self,
+        ct_m: &kem::Ciphertext,
+        ss_m: &kem::SharedSecret,
+        ct_x: &[u8],
+        ek_x: &[u8],
+        pk_x: &[u8],
+    ) -> Result<(Ciphertext, SharedSecret)> {
+        let ek_x = x25519_secret(Operation::Encapsulate, ek_x)?;
+        let ss_x = ek_x.diffie_hellman(&x25519_public(Operation::Encapsulate, pk_x)?);
+        let ss = combine(&[ss_m.as_ref(), ss_x.as_bytes(), ct_x, pk_x, XWING_LABEL]);
+        let ct = Ciphertext {
+            bytes: [ct_m.as_ref(), ct_x].concat(),
+        };
+        Ok((ct, ss))
+    }
+}
+
+/// Name of [`HybridKem`] in errors, as its label is not `'static`
+const HYBRID: &str = "hybrid KEM";
+
+/// Generic hybrid of a liboqs KEM and elliptic-curve Diffie-Hellman
+///
+/// Public keys, secret keys and ciphertexts are the concatenation of those of the KEM
+/// and of the [`Group`]. The shared secret is the SHA3-256 hash of
+/// the KEM shared secret, the Diffie-Hellman shared secret, the KEM ciphertext,
+/// the ephemeral and static Diffie-Hellman public keys, and a label naming the
+/// KEM and the group, such as `ML-KEM-768+P-256`.
+///
+/// Unlike [`XWing`], this hashes the KEM ciphertext, so that any KEM can be used.
+///
+/// # Example
+/// ```rust
+/// # if !cfg!(feature = "ml_kem") { return; }
+/// use oqs::hybrid::{Group, HybridKem};
+/// oqs::init();
+/// let kem = HybridKem::new(oqs::kem::Algorithm::MlKem768, Group::P256).unwrap();
+/// let (pk, sk) = kem.keypair().unwrap();
+/// let (ct, ss) = kem.encapsulate(&pk).unwrap();
+/// let ss2 = kem.decapsulate(&sk, &ct).unwrap();
+/// assert_eq!(ss, ss2);
+/// ```
+pub struct HybridKem {
+    kem: Kem,
+    group: Group,
+    label: Vec<u8>,
+}
+
+impl HybridKem {
+    /// Construct a new hybrid of `algorithm` and `group`
+    pub fn new(algorithm: kem::Algorithm, group: Group) -> Result<Self> {
+        let kem = Kem::new(algorithm)?;
+        let label = [algorithm.name(), "+", group.name()].concat().into_bytes();
+        Ok(Self { kem, group, label })
+    }
+
+    /// Get the KEM algorithm used by this hybrid
+    pub fn algorithm(&self) -> kem::Algorithm {
+        self.kem.algorithm()
+    }
+
+    /// Get the elliptic-curve group used by this hybrid
+    pub fn group(&self) -> Group {
+        self.group
+    }
+
+    /// Get the length of the public key
+    pub fn length_public_key(&self) -> usize {
+        self.kem.length_public_key() + self.group.length_public_key()
+    }
+
+    /// Get the length of the secret key
+    pub fn length_secret_key(&self) -> usize {
+        self.kem.length_secret_key() + self.group.length_secret_key()
+    }
+
+    /// Get the length of the ciphertext
+    pub fn length_ciphertext(&self) -> usize {
+        self.kem.length_ciphertext() + self.group.length_public_key()
+    }
+
+    /// Get the length of a shared secret
+    pub fn length_shared_secret(&self) -> usize {
+        32
+    }
+
+    from_bytes!();
+
+    /// Generate a new keypair
+    pub fn keypair(&self) -> Result<(PublicKey, SecretKey)> {
+        let (pk_kem, sk_kem) = self.kem.keypair()?;
+        let (pk_dh, sk_dh) = self.group.keypair();
+        let pk = PublicKey {
+            bytes: [pk_kem.as_ref(), &pk_dh].concat(),
+        };
+        let sk = SecretKey {
+            bytes: [sk_kem.as_ref(), sk_dh.as_ref()].concat(),
+        };
+        Ok((pk, sk))
+    }
+
+    /// Encapsulate to the provided public key
+    pub fn encapsulate<'a, P: Into<PublicKeyRef<'a>>>(
+        &self,
+        pk: P,
+    ) -> Result<(Ciphertext, SharedSecret)> {
+        let pk = pk.into();
+        let operation = Operation::Encapsulate;
+        check_length(operation, HYBRID, self.length_public_key(), pk.len())?;
+        let (pk_kem, pk_dh) = pk.split_at(self.kem.length_public_key());
+        let pk_kem = self
+            .kem
+            .public_key_from_bytes(pk_kem)
+            .expect("split at the KEM public key length");
+        let (ct_kem, ss_kem) = self.kem.encapsulate(pk_kem)?;
+        let (ct_dh, ek_dh) = self.group.keypair();
+        let ss_dh = self.group.diffie_hellman(operation, &ek_dh.bytes, pk_dh)?;
+        let ss = self.combine(&ss_kem, &ss_dh, ct_kem.as_ref(), &ct_dh, pk_dh);
+        let ct = Ciphertext {
+            bytes: [ct_kem.as_ref(), &ct_dh].concat(),
+        };
+        Ok((ct, ss))
+    }
+
+    /// Decapsulate the provided ciphertext
+    pub fn decapsulate<'a, 'b, S: Into<SecretKeyRef<'a>>, C: Into<CiphertextRef<'b>>>(
+        &self,
+        sk: S,
+        ct: C,
+    ) -> Result<SharedSecret> {
+        let sk = sk.into();
+        let ct = ct.into();
+        let operation = Operation::Decapsulate;
+        check_length(operation, HYBRID, self.length_secret_key(), sk.len())?;
+        check_length(operation, HYBRID, self.length_ciphertext(), ct.len())?;
+        let (sk_kem, sk_dh) = sk.split_at(self.kem.length_secret_key());
+        let (ct_kem, ct_dh) = ct.split_at(self.kem.length_ciphertext());
+        let sk_kem = self
+            .kem
+            .secret_key_from_bytes(sk_kem)
+            .expect("split at the KEM secret key length");
+        let ct_kem = self
+            .kem
+            .ciphertext_from_bytes(ct_kem)
+            .expect("split at the KEM ciphertext length");
+        let ss_kem = self.kem.decapsulate(sk_kem, ct_kem)?;
+        let ss_dh = self.group.diffie_hellman(operation, sk_dh, ct_dh)?;
+        let pk_dh = self.group.public_key(sk_dh)?;
+        Ok(self.combine(&ss_kem, &ss_dh, &ct_kem, ct_dh, &pk_dh))
+    }
+
+    fn combine(
+        &self,
+        ss_kem: &kem::SharedSecret,
+        ss_dh: &SharedSecret,
+        ct_kem: &[u8],
+        ct_dh: &[u8],
+        pk_dh: &[u8],
+    ) -> SharedSecret {
+        combine(&[
+            ss_kem.as_ref(),
+            ss_dh.as_ref(),
+            ct_kem,
+            ct_dh,
+            pk_dh,
+            &self.label,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Parse the `key = value` records of `testdata/xwing.txt`
+    fn xwing_vectors() -> Vec<Vec<(&'static str, Vec<u8>)>> {
+        let unhex = |s: &str| {
+            (0..s.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+                .collect()
+        };
+        include_str!("../testdata/xwing.txt")
+            .split("\n\n")
+            .map(|record| {
+                record
+                    .lines()
+                    .filter(|line| !line.starts_with('#'))
+                    .filter_map(|line| line.split_once(" = "))
+                    .map(|(key, value)| (key, unhex(value)))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|record| !record.is_empty())
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "ml_kem")]
+    fn test_xwing_vectors() -> Result<()> {
+        let _lock = crate::rand::test_lock();
+        crate::init();
+        let xwing = XWing::new()?;
+        let vectors = xwing_vectors();
+        assert_eq!(vectors.len(), 3);
+        for record in vectors {
+            let get = |key| &record.iter().find(|(k, _)| *k == key).unwrap().1;
+            let (pk, sk) = xwing.keypair_derand(get("seed"))?;
+            assert_eq!(pk.as_ref(), get("pk").as_slice());
+            let (ct, ss) = xwing.encapsulate_derand(&pk, get("eseed"))?;
+            assert_eq!(ct.as_ref(), get("ct").as_slice());
+            assert_eq!(ss.as_ref(), get("ss").as_slice());
+            assert_eq!(xwing.decapsulate(&sk, &ct)?, ss);
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ml_kem")]
+    fn test_xwing() -> Result<()> {
+        crate::init();
+        let xwing = XWing::new()?;
+        let (pk, sk) = xwing.keypair()?;
+        assert_eq!(pk.len(), 1216);
+        assert_eq!(xwing.public_key(&sk)?, pk);
+        let (ct, ss) = xwing.encapsulate(&pk)?;
+        assert_eq!(ct.len(), 1120);
+        assert_eq!(xwing.decapsulate(&sk, &ct)?, ss);
+
+        let mut tampered = ct.clone().into_vec();
+        tampered[0] ^= 1;
+        let tampered = xwing.ciphertext_from_bytes(&tampered).unwrap();
+        assert_ne!(xwing.decapsulate(&sk, tampered)?, ss);
+
+        assert!(matches!(
+            xwing.encapsulate_derand(&pk, &[0; 32]),
+            Err(Error::WrongLength {
+                operation: Operation::Encapsulate,
+                algorithm: "X-Wing",
+                expected: 64,
+                actual: 32,
+            })
+        ));
+        assert!(xwing.public_key_from_bytes(&pk.as_ref()[1..]).is_none());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ml_kem")]
+    fn test_hybrid() -> Result<()> {
+        crate::init();
+        for group in [Group::X25519, Group::P256, Group::P384] {
+            let kem = HybridKem::new(kem::Algorithm::MlKem768, group)?;
+            let (pk, sk) = kem.keypair()?;
+            assert_eq!(pk.len(), kem.length_public_key());
+            assert_eq!(sk.len(), kem.length_secret_key());
+            let (ct, ss) = kem.encapsulate(&pk)?;
+            assert_eq!(ct.len(), kem.length_ciphertext());
+            assert_eq!(kem.decapsulate(&sk, &ct)?, ss);
+
+            let mut tampered = ct.clone().into_vec();
+            tampered[0] ^= 1;
+            let tampered = kem.ciphertext_from_bytes(&tampered).unwrap();
+            assert_ne!(kem.decapsulate(&sk, tampered)?, ss);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_lengths() {
+        for group in [Group::X25519, Group::P256, Group::P384] {
+            let (pk, sk) = group.keypair();
+            assert_eq!(pk.len(), group.length_public_key());
+            assert_eq!(sk.len(), group.length_secret_key());
+            let (ct, ek) = group.keypair();
+            let ss = group.diffie_hellman(Operation::Encapsulate, &ek.bytes, &pk);
+            let ss2 = group.diffie_hellman(Operation::Decapsulate, &sk.bytes, &ct);
+            assert_eq!(ss.unwrap(), ss2.unwrap());
+        }
+    }
+}
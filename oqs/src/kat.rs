@@ -0,0 +1,289 @@
+//! Known-answer-test harness
+//!
+//! liboqs ships the SHA-256 hashes of the `.rsp` output of its KAT programs
+//! in `tests/KATs/{kem,sig}/kats.json`. We regenerate that output with the NIST DRBG
+//! from [`crate::rand`] and compare the hashes, so that a miscompiled or mislinked
+//! liboqs shows up as a mismatch for the affected algorithm.
+//! Full `.rsp` files found in the same directories are compared record by record.
+//!
+//! Set `OQS_KATS_DIR` to use a different `KATs` directory,
+//! and `OQS_KAT_ALL=1` to replay all 100 records instead of only the first one.
+//! An algorithm without known-answer tests fails unless `OQS_KAT_ALLOW_MISSING=1` is set.
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::rand::{with_deterministic_rng, NistKatRng};
+use crate::*;
+
+/// A record of a `.rsp` file, mapping field names to their values
+pub(crate) type Record = BTreeMap<String, String>;
+
+/// Parse the NIST `.rsp` format
+///
+/// Records are groups of `name = value` lines separated by blank lines.
+/// Lines starting with `#` are comments.
+pub(crate) fn parse_rsp(contents: &str) -> Vec<Record> {
+    let mut records = Vec::new();
+    let mut record = Record::new();
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.is_empty() {
+            if !record.is_empty() {
+                records.push(std::mem::take(&mut record));
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once('=') {
+            record.insert(name.trim().to_owned(), value.trim().to_owned());
+        }
+    }
+    if !record.is_empty() {
+        records.push(record);
+    }
+    records
+}
+
+fn hex(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        // OQS_fprintBstr prints empty strings as `00`
+        return "00".to_owned();
+    }
+    bytes.iter().fold(String::new(), |mut out, b| {
+        let _ = write!(out, "{b:02X}");
+        out
+    })
+}
+
+fn unhex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).expect("invalid hex in KAT file"))
+        .collect()
+}
+
+fn kats_dir() -> PathBuf {
+    std::env::var_os("OQS_KATS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../oqs-sys/liboqs/tests/KATs")
+        })
+}
+
+fn replay_all() -> bool {
+    std::env::var("OQS_KAT_ALL").is_ok_and(|v| v != "0")
+}
+
+fn allow_missing() -> bool {
+    std::env::var("OQS_KAT_ALLOW_MISSING").is_ok_and(|v| v != "0")
+}
+
+/// Look up the expected hash of the KAT output of `name` in `{kind}/kats.json`
+fn expected_hash(kind: &str, name: &str) -> Option<String> {
+    let path = kats_dir().join(kind).join("kats.json");
+    let kats: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).ok()?)
+        .expect("kats.json is not valid JSON");
+    match &kats[name] {
+        serde_json::Value::String(hash) => Some(hash.clone()),
+        entry => entry[if replay_all() { "all" } else { "single" }]
+            .as_str()
+            .map(str::to_owned),
+    }
+}
+
+/// Find a full `{name}.rsp` file below `{kind}`
+fn find_rsp(kind: &str, name: &str) -> Option<PathBuf> {
+    fn search(dir: &Path, file_name: &str) -> Option<PathBuf> {
+        for entry in std::fs::read_dir(dir).ok()?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if let Some(found) = search(&path, file_name) {
+                    return Some(found);
+                }
+            } else if path.file_name().is_some_and(|n| n == file_name) {
+                return Some(path);
+            }
+        }
+        None
+    }
+    search(&kats_dir().join(kind), &format!("{name}.rsp"))
+}
+
+/// Draws the per-record seeds (and messages) like the NIST `PQCgenKAT` programs
+fn seed_rng() -> NistKatRng {
+    let mut entropy_input = [0u8; 48];
+    for (i, byte) in entropy_input.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    NistKatRng::new(&entropy_input)
+}
+
+fn kem_record(kem: &kem::Kem, count: usize, seed: &[u8; 48]) -> Result<Record> {
+    let (pk, sk, ct, ss) = with_deterministic_rng(seed, || -> Result<_> {
+        let (pk, sk) = kem.keypair()?;
+        let (ct, ss) = kem.encapsulate(&pk)?;
+        Ok((pk, sk, ct, ss))
+    })?;
+    let ss2 = kem.decapsulate(&sk, &ct)?;
+    assert_eq!(ss, ss2, "{}: shared secret not equal!", kem.algorithm());
+    Ok(Record::from([
+        ("count".to_owned(), count.to_string()),
+        ("seed".to_owned(), hex(seed)),
+        ("pk".to_owned(), hex(pk.as_ref())),
+        ("sk".to_owned(), hex(sk.as_ref())),
+        ("ct".to_owned(), hex(ct.as_ref())),
+        ("ss".to_owned(), hex(ss.as_ref())),
+    ]))
+}
+
+/// Combine message and signature the way the reference `crypto_sign` does
+fn signed_message(alg: sig::Algorithm, msg: &[u8], signature: &[u8]) -> Vec<u8> {
+    use sig::Algorithm::*;
+    match alg {
+        Falcon512 | Falcon1024 => {
+            // sig_len (2 bytes, big endian) || nonce (40 bytes) || msg || header || sig
+            let mut sm = Vec::with_capacity(signature.len() + msg.len() + 2);
+            sm.extend_from_slice(&(signature.len() as u16).to_be_bytes());
+            sm.extend_from_slice(&signature[1..41]);
+            sm.extend_from_slice(msg);
+            // The submission used 0x2X headers for compressed signatures
+            sm.push(0x20 + (signature[0] & 0x0F));
+            sm.extend_from_slice(&signature[41..]);
+            sm
+        }
+        _ if alg.name().starts_with("OV-") || alg.name().starts_with("cross-") => {
+            [msg, signature].concat()
+        }
+        _ => [signature, msg].concat(),
+    }
+}
+
+fn sig_record(sig: &sig::Sig, count: usize, seed: &[u8; 48], msg: &[u8]) -> Result<Record> {
+    let (pk, sk, signature) = with_deterministic_rng(seed, || -> Result<_> {
+        let (pk, sk) = sig.keypair()?;
+        let signature = sig.sign(msg, &sk)?;
+        Ok((pk, sk, signature))
+    })?;
+    sig.verify(msg, &signature, &pk)?;
+    let sm = signed_message(sig.algorithm(), msg, signature.as_ref());
+    Ok(Record::from([
+        ("count".to_owned(), count.to_string()),
+        ("seed".to_owned(), hex(seed)),
+        ("mlen".to_owned(), msg.len().to_string()),
+        ("msg".to_owned(), hex(msg)),
+        ("pk".to_owned(), hex(pk.as_ref())),
+        ("sk".to_owned(), hex(sk.as_ref())),
+        ("smlen".to_owned(), sm.len().to_string()),
+        ("sm".to_owned(), hex(&sm)),
+    ]))
+}
+
+/// Print records in the field order of the liboqs KAT programs
+fn format_rsp(records: &[Record], fields: &[&str]) -> String {
+    let mut out = String::new();
+    for record in records {
+        for field in fields {
+            let _ = writeln!(out, "{} = {}", field, record[*field]);
+        }
+        // The NIST program generates KAT response files with a trailing newline.
+        out.push('\n');
+    }
+    out
+}
+
+const KEM_FIELDS: &[&str] = &["count", "seed", "pk", "sk", "ct", "ss"];
+const SIG_FIELDS: &[&str] = &["count", "seed", "mlen", "msg", "pk", "sk", "smlen", "sm"];
+
+/// Compare the generated records against the expected hash and `.rsp` file
+fn compare(kind: &str, name: &str, records: &[Record], fields: &[&str]) {
+    let mut checked = false;
+    if let Some(expected) = expected_hash(kind, name) {
+        let output = format_rsp(records, fields);
+        let hash = hex(&Sha256::digest(output.as_bytes())).to_lowercase();
+        assert_eq!(hash, expected.to_lowercase(), "{name}: KAT hash mismatch");
+        checked = true;
+    }
+    if let Some(path) = find_rsp(kind, name) {
+        let expected = parse_rsp(&std::fs::read_to_string(&path).unwrap());
+        for (record, expected) in records.iter().zip(&expected) {
+            for field in fields {
+                if let Some(value) = expected.get(*field) {
+                    assert_eq!(
+                        &record[*field],
+                        value,
+                        "{name}: `{field}` of record {} differs from {}",
+                        record["count"],
+                        path.display()
+                    );
+                }
+            }
+        }
+        checked = true;
+    }
+    assert!(
+        checked || allow_missing(),
+        "{name}: no known-answer tests found, set OQS_KAT_ALLOW_MISSING=1 to skip"
+    );
+}
+
+/// Replay the known-answer tests of a KEM
+pub(crate) fn check_kem(algorithm: kem::Algorithm) -> Result<()> {
+    let _lock = crate::rand::test_lock();
+    let kem = kem::Kem::new(algorithm)?;
+    let mut seeds = seed_rng();
+    let count = if replay_all() { 100 } else { 1 };
+    let records = (0..count)
+        .map(|i| {
+            let mut seed = [0u8; 48];
+            seeds.fill_bytes(&mut seed);
+            kem_record(&kem, i, &seed)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    compare("kem", algorithm.name(), &records, KEM_FIELDS);
+    Ok(())
+}
+
+/// Replay the known-answer tests of a signature scheme
+pub(crate) fn check_sig(algorithm: sig::Algorithm) -> Result<()> {
+    let _lock = crate::rand::test_lock();
+    let sig = sig::Sig::new(algorithm)?;
+    let mut seeds = seed_rng();
+    let count = if replay_all() { 100 } else { 1 };
+    let records = (0..count)
+        .map(|i| {
+            let mut seed = [0u8; 48];
+            seeds.fill_bytes(&mut seed);
+            let mut msg = vec![0u8; 33 * (i + 1)];
+            seeds.fill_bytes(&mut msg);
+            sig_record(&sig, i, &seed, &msg)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    compare("sig", algorithm.name(), &records, SIG_FIELDS);
+    Ok(())
+}
+
+#[test]
+fn test_rsp_roundtrip() {
+    let records = vec![
+        Record::from([
+            ("count".to_owned(), "0".to_owned()),
+            ("seed".to_owned(), hex(&[0x06, 0x15])),
+        ]),
+        Record::from([
+            ("count".to_owned(), "1".to_owned()),
+            ("seed".to_owned(), hex(&[0xD8, 0x1C])),
+        ]),
+    ];
+    let output = format_rsp(&records, &["count", "seed"]);
+    assert_eq!(
+        output,
+        "count = 0\nseed = 0615\n\ncount = 1\nseed = D81C\n\n"
+    );
+    let parsed = parse_rsp(&format!("# header\n\n{output}"));
+    assert_eq!(parsed, records);
+    assert_eq!(unhex(&parsed[1]["seed"]), [0xD8, 0x1C]);
+}
@@ -11,6 +11,7 @@ use cstr_core::CStr;
 #[cfg(feature = "std")]
 use std::ffi::CStr;
 
+use const_oid::ObjectIdentifier;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -19,13 +20,13 @@ use crate::newtype_buffer;
 use crate::*;
 
 newtype_buffer!(PublicKey, PublicKeyRef);
-newtype_buffer!(SecretKey, SecretKeyRef);
+newtype_buffer!(secret SecretKey, SecretKeyRef);
 newtype_buffer!(Ciphertext, CiphertextRef);
-newtype_buffer!(SharedSecret, SharedSecretRef);
-newtype_buffer!(KeypairSeed, KeypairSeedRef);
+newtype_buffer!(secret SharedSecret, SharedSecretRef);
+newtype_buffer!(secret KeypairSeed, KeypairSeedRef);
 
 macro_rules! implement_kems {
-    { $(($feat: literal) $kem: ident: $oqs_id: ident),* $(,)? } => (
+    { $(($feat: literal) $kem: ident: $alg: ident),* $(,)? } => (
 
         /// Supported algorithms by OQS
         ///
@@ -41,139 +42,359 @@ macro_rules! implement_kems {
             )*
         }
 
+        impl Algorithm {
+            /// All algorithms, including those that are not enabled
+            pub const ALL: &'static [Algorithm] = &[$(Algorithm::$kem,)*];
+        }
+
         fn algorithm_to_id(algorithm: Algorithm) -> *const libc::c_char {
-            let id: &[u8] = match algorithm {
-                $(
-                    Algorithm::$kem => &ffi::$oqs_id[..],
-                )*
+            let id: &[u8] = paste::paste! {
+                match algorithm {
+                    $(
+                        Algorithm::$kem => &ffi::[<OQS_KEM_alg_ $alg>][..],
+                    )*
+                }
             };
             id as *const _ as *const libc::c_char
         }
 
+        /// The `oqsconfig.h` macro that enables the algorithm
+        fn algorithm_enable_macro(algorithm: Algorithm) -> &'static str {
+            match algorithm {
+                $(
+                    Algorithm::$kem => concat!("OQS_ENABLE_KEM_", stringify!($alg)),
+                )*
+            }
+        }
+
         $(
-            #[cfg(test)]
-            #[allow(non_snake_case)]
-            mod $kem {
-                use super::*;
+            paste::paste! {
+                #[doc = "Marker type for [`Algorithm::" $kem "`], see [`KemAlgorithm`]"]
+                #[cfg(feature = $feat)]
+                #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+                pub struct $kem;
 
-                #[test]
                 #[cfg(feature = $feat)]
-                fn test_encaps_decaps() -> Result<()> {
-                    crate::init();
-
-                    let alg = Algorithm::$kem;
-                    let kem = Kem::new(alg)?;
-                    let (pk, sk) = kem.keypair()?;
-                    let (ct, ss1) = kem.encapsulate(&pk)?;
-                    let ss2 = kem.decapsulate(&sk, &ct)?;
-                    assert_eq!(ss1, ss2, "shared secret not equal!");
-                    Ok(())
+                impl KemAlgorithm for $kem {
+                    const ALGORITHM: Algorithm = Algorithm::$kem;
+                    type PublicKeyBytes = [u8; Algorithm::$kem.info().length_public_key];
+                    type SecretKeyBytes = [u8; Algorithm::$kem.info().length_secret_key];
+                    type CiphertextBytes = [u8; Algorithm::$kem.info().length_ciphertext];
+                    type SharedSecretBytes = [u8; Algorithm::$kem.info().length_shared_secret];
                 }
+            }
+        )*
 
-                #[test]
-                #[cfg(feature = $feat)]
-                fn test_encaps_decaps_derand() -> Result<()> {
-                    use crate::ffi::rand::OQS_randombytes;
-                    crate::init();
-
-                    let alg = Algorithm::$kem;
-                    let kem = Kem::new(alg)?;
-                    let mut seed = KeypairSeed {
-                        bytes: Vec::with_capacity(kem.length_keypair_seed()),
-                    };
-                    unsafe {
-                        // On some systems, getentropy fails if given a zero-length array
-                        if (kem.length_keypair_seed() > 0) {
-                            OQS_randombytes(seed.bytes.as_mut_ptr(), kem.length_keypair_seed());
+        #[cfg(test)]
+        mod test {
+            use super::*;
+
+            $(
+                #[allow(non_snake_case)]
+                mod $kem {
+                    use super::*;
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_encaps_decaps() -> Result<()> {
+                        crate::init();
+
+                        let alg = Algorithm::$kem;
+                        let kem = Kem::new(alg)?;
+                        let (pk, sk) = kem.keypair()?;
+                        let (ct, ss1) = kem.encapsulate(&pk)?;
+                        let ss2 = kem.decapsulate(&sk, &ct)?;
+                        assert_eq!(ss1, ss2, "shared secret not equal!");
+                        Ok(())
+                    }
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_encaps_decaps_derand() -> Result<()> {
+                        use crate::ffi::rand::OQS_randombytes;
+                        crate::init();
+
+                        let alg = Algorithm::$kem;
+                        let kem = Kem::new(alg)?;
+                        let mut seed = KeypairSeed {
+                            bytes: Vec::with_capacity(kem.length_keypair_seed()),
+                        };
+                        unsafe {
+                            // On some systems, getentropy fails if given a zero-length array
+                            if (kem.length_keypair_seed() > 0) {
+                                OQS_randombytes(seed.bytes.as_mut_ptr(), kem.length_keypair_seed());
+                            }
+                            seed.bytes.set_len(kem.length_keypair_seed());
+                        }
+                        let result = kem.keypair_derand(&seed);
+                        // expect Error::Unsupported for KEMs with this API disabled
+                        if !kem.supports_derand() {
+                            return result.map_or_else(|e| { match e { Error::Unsupported => Ok(()), _ => Err(Error::Error) } }, |_| Err(Error::Error));
                         }
-                        seed.bytes.set_len(kem.length_keypair_seed());
+                        let (pk, sk) = result?;
+                        let (ct, ss1) = kem.encapsulate(&pk)?;
+                        let ss2 = kem.decapsulate(&sk, &ct)?;
+                        assert_eq!(ss1, ss2, "shared secret not equal!");
+                        Ok(())
                     }
-                    let result = kem.keypair_derand(&seed);
-                    // expect Error::Error for KEMs with this API disabled
-                    if (kem.length_keypair_seed() == 0) {
-                        return result.map_or_else(|e| { match e { Error::Error => Ok(()), _ => Err(Error::Error) } }, |_| Err(Error::Error));
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_encaps_decaps_into() -> Result<()> {
+                        crate::init();
+
+                        let kem = Kem::new(Algorithm::$kem)?;
+                        let mut pk = alloc::vec![0; kem.length_public_key()];
+                        let mut sk = alloc::vec![0; kem.length_secret_key()];
+                        let mut ct = alloc::vec![0; kem.length_ciphertext()];
+                        let mut ss1 = alloc::vec![0; kem.length_shared_secret()];
+                        let mut ss2 = alloc::vec![0; kem.length_shared_secret() + 1];
+                        kem.keypair_into(&mut pk, &mut sk)?;
+                        let pk = kem.public_key_from_bytes(&pk).unwrap();
+                        let sk = kem.secret_key_from_bytes(&sk).unwrap();
+                        kem.encapsulate_into(pk, &mut ct, &mut ss1)?;
+                        let ct = kem.ciphertext_from_bytes(&ct).unwrap();
+                        kem.decapsulate_into(sk, ct, &mut ss2)?;
+                        assert_eq!(ss1, ss2[..ss1.len()], "shared secret not equal!");
+                        assert_eq!(kem.decapsulate(sk, ct)?.as_ref(), ss1);
+
+                        let mut short = alloc::vec![0; kem.length_shared_secret() - 1];
+                        assert!(matches!(
+                            kem.decapsulate_into(sk, ct, &mut short),
+                            Err(Error::WrongLength { operation: Operation::Decapsulate, .. })
+                        ));
+                        let mut short = alloc::vec![0; kem.length_ciphertext() - 1];
+                        let error = kem.encapsulate_into(pk, &mut short, &mut ss1).unwrap_err();
+                        assert!(matches!(
+                            error,
+                            Error::WrongLength {
+                                operation: Operation::Encapsulate,
+                                expected,
+                                actual,
+                                ..
+                            } if expected == kem.length_ciphertext() && actual == short.len()
+                        ));
+                        Ok(())
                     }
-                    let (pk, sk) = result?;
-                    let (ct, ss1) = kem.encapsulate(&pk)?;
-                    let ss2 = kem.decapsulate(&sk, &ct)?;
-                    assert_eq!(ss1, ss2, "shared secret not equal!");
-                    Ok(())
-                }
 
-                #[test]
-                fn test_enabled() {
-                    crate::init();
-                    if cfg!(feature = $feat) {
-                        assert!(Algorithm::$kem.is_enabled());
-                    } else {
-                        assert!(!Algorithm::$kem.is_enabled())
+                    #[test]
+                    #[cfg(all(feature = $feat, feature = "deterministic_rng", feature = "std"))]
+                    fn test_kat() -> Result<()> {
+                        crate::init();
+                        crate::kat::check_kem(Algorithm::$kem)
                     }
-                }
 
-                #[test]
-                fn test_name() {
-                    let algo = Algorithm::$kem;
-                    // Just make sure the name impl does not panic or crash.
-                    let name = algo.name();
-                    #[cfg(feature = "std")]
-                    assert_eq!(name, algo.to_string());
-                    // ... And actually contains something.
-                    assert!(!name.is_empty());
-                }
+                    #[test]
+                    #[cfg(all(feature = $feat, feature = "kem"))]
+                    fn test_kem_traits() -> Result<()> {
+                        use ::kem::{Decapsulate, Encapsulate};
+                        crate::init();
+                        let mut rng = ::rand::rngs::OsRng;
+                        let dk = DecapsulationKey::generate(Algorithm::$kem, &mut rng)?;
+                        let ek = dk.encapsulation_key();
+                        let (ct, ss1): (Ciphertext, SharedSecret) = ek.encapsulate(&mut rng)?;
+                        let ss2: SharedSecret = dk.decapsulate(&ct)?;
+                        assert_eq!(ss1, ss2, "shared secret not equal!");
+                        let ct: Vec<u8> = ct.into();
+                        let ss3: SharedSecret = dk.decapsulate(&ct)?;
+                        assert_eq!(ss1, ss3, "shared secret not equal!");
+                        assert!(matches!(
+                            Decapsulate::<_, SharedSecret>::decapsulate(&dk, &ct[1..].to_vec()),
+                            Err(Error::WrongLength {
+                                operation: Operation::Decapsulate,
+                                ..
+                            })
+                        ));
+                        let pk = ek.public_key();
+                        assert_eq!(&EncapsulationKey::from_bytes(Algorithm::$kem, &pk)?, ek);
+                        Ok(())
+                    }
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_typed() -> Result<()> {
+                        use crate::kem::typed::{self, KemAlgorithm};
+                        type A = crate::kem::$kem;
+                        crate::init();
 
-                #[test]
-                fn test_get_algorithm_back() {
-                    let algorithm = Algorithm::$kem;
-                    if algorithm.is_enabled() {
-                        let kem = Kem::new(algorithm).unwrap();
-                        assert_eq!(algorithm, kem.algorithm());
+                        let kem = Kem::new(A::ALGORITHM)?;
+                        assert_eq!(typed::PublicKey::<A>::LENGTH, kem.length_public_key());
+                        assert_eq!(typed::SecretKey::<A>::LENGTH, kem.length_secret_key());
+                        assert_eq!(typed::Ciphertext::<A>::LENGTH, kem.length_ciphertext());
+                        assert_eq!(typed::SharedSecret::<A>::LENGTH, kem.length_shared_secret());
+                        let (pk, sk) = A::keypair()?;
+                        let (ct, ss1) = A::encapsulate(&pk)?;
+                        let ss2 = A::decapsulate(&sk, &ct)?;
+                        assert_eq!(ss1, ss2, "shared secret not equal!");
+                        // typed buffers also work with the dynamic API
+                        let ss3 = kem.decapsulate(&sk, &ct)?;
+                        assert_eq!(ss1.as_ref(), ss3.as_ref(), "shared secret not equal!");
+                        assert_eq!(typed::Ciphertext::<A>::from_bytes(ct.as_ref())?, ct);
+                        assert!(matches!(
+                            typed::PublicKey::<A>::from_bytes(&pk.as_ref()[1..]),
+                            Err(Error::WrongLength {
+                                operation: Operation::Decode,
+                                ..
+                            })
+                        ));
+                        Ok(())
                     }
-                }
 
-                #[test]
-                fn test_version() {
-                    if let Ok(kem) = Kem::new(Algorithm::$kem) {
-                        // Just make sure the version can be called without panic
-                        let version = kem.version();
+                    #[test]
+                    #[cfg(all(feature = $feat, feature = "pkcs8"))]
+                    fn test_pkcs8() -> Result<()> {
+                        use pkcs8::LineEnding;
+                        crate::init();
+
+                        let kem = Kem::new(Algorithm::$kem)?;
+                        let (pk, sk) = kem.keypair()?;
+                        if Algorithm::$kem.oid().is_none() {
+                            assert!(matches!(kem.public_key_to_der(&pk), Err(Error::Unsupported)));
+                            return Ok(());
+                        }
+                        let der = kem.public_key_to_der(&pk)?;
+                        assert_eq!(kem.public_key_from_der(der.as_bytes())?, pk);
+                        let pem = kem.public_key_to_pem(&pk, LineEnding::LF)?;
+                        assert_eq!(kem.public_key_from_pem(&pem)?, pk);
+                        let der = kem.secret_key_to_der(&sk, &pk)?;
+                        assert_eq!(kem.secret_key_from_der(der.as_bytes())?, sk);
+                        let pem = kem.secret_key_to_pem(&sk, &pk, LineEnding::LF)?;
+                        assert_eq!(kem.secret_key_from_pem(&pem)?, sk);
+                        Ok(())
+                    }
+
+                    #[test]
+                    fn test_enabled() {
+                        crate::init();
+                        if cfg!(feature = $feat) {
+                            assert!(Algorithm::$kem.is_enabled());
+                        } else {
+                            assert!(!Algorithm::$kem.is_enabled())
+                        }
+                    }
+
+                    #[test]
+                    fn test_name() {
+                        let algo = Algorithm::$kem;
+                        // Just make sure the name impl does not panic or crash.
+                        let name = algo.name();
+                        #[cfg(feature = "std")]
+                        assert_eq!(name, algo.to_string());
                         // ... And actually contains something.
-                        assert!(!version.is_empty());
+                        assert!(!name.is_empty());
+                    }
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_info() -> Result<()> {
+                        crate::init();
+                        let kem = Kem::new(Algorithm::$kem)?;
+                        let info = Algorithm::$kem.info();
+                        assert_eq!(info.claimed_nist_level, kem.claimed_nist_level());
+                        assert_eq!(info.is_ind_cca, kem.is_ind_cca());
+                        assert_eq!(info.length_public_key, kem.length_public_key());
+                        assert_eq!(info.length_secret_key, kem.length_secret_key());
+                        assert_eq!(info.length_ciphertext, kem.length_ciphertext());
+                        assert_eq!(info.length_shared_secret, kem.length_shared_secret());
+                        Ok(())
+                    }
+
+                    #[test]
+                    fn test_from_str() {
+                        let algorithm = Algorithm::$kem;
+                        assert_eq!(algorithm.name().parse::<Algorithm>().ok(), Some(algorithm));
+                        assert!(Algorithm::iter().any(|alg| alg == algorithm));
+                        assert_eq!(
+                            Algorithm::enabled().any(|alg| alg == algorithm),
+                            algorithm.is_enabled()
+                        );
+                    }
+
+                    #[test]
+                    fn test_from_oid() {
+                        if let Some(oid) = Algorithm::$kem.oid() {
+                            assert_eq!(Algorithm::from_oid(oid), Some(Algorithm::$kem));
+                        }
+                    }
+
+                    #[test]
+                    fn test_get_algorithm_back() {
+                        let algorithm = Algorithm::$kem;
+                        if algorithm.is_enabled() {
+                            let kem = Kem::new(algorithm).unwrap();
+                            assert_eq!(algorithm, kem.algorithm());
+                        }
+                    }
+
+                    #[test]
+                    fn test_version() {
+                        if let Ok(kem) = Kem::new(Algorithm::$kem) {
+                            // Just make sure the version can be called without panic
+                            let version = kem.version();
+                            // ... And actually contains something.
+                            assert!(!version.is_empty());
+                        }
                     }
                 }
+            )*
+
+            #[test]
+            fn test_liboqs_identifiers() {
+                crate::init();
+                // The liboqs release that oqs-sys bundles, and its algorithms we don't support.
+                // Newer releases may add algorithms, so coverage is only checked for this one.
+                const PINNED_VERSION: &str = "0.13.0";
+                const UNSUPPORTED: &[&str] = &[];
+                let pinned = crate::version() == PINNED_VERSION;
+                let count = unsafe { ffi::OQS_KEM_alg_count() };
+                for i in 0..count {
+                    let id = unsafe { CStr::from_ptr(ffi::OQS_KEM_alg_identifier(i as usize)) };
+                    let name = id.to_str().unwrap();
+                    match name.parse::<Algorithm>() {
+                        Ok(algorithm) => assert_eq!(algorithm.name(), name),
+                        Err(_) => assert!(
+                            !pinned || UNSUPPORTED.contains(&name),
+                            "{name} has no Algorithm variant"
+                        ),
+                    }
+                }
+                assert!(Algorithm::enabled().all(|alg| alg.is_enabled()));
+                assert!("unknown".parse::<Algorithm>().is_err());
             }
-        )*
+        }
     )
 }
 
 implement_kems! {
-    ("bike") BikeL1: OQS_KEM_alg_bike_l1,
-    ("bike") BikeL3: OQS_KEM_alg_bike_l3,
-    ("bike") BikeL5: OQS_KEM_alg_bike_l5,
-    ("classic_mceliece") ClassicMcEliece348864: OQS_KEM_alg_classic_mceliece_348864,
-    ("classic_mceliece") ClassicMcEliece348864f: OQS_KEM_alg_classic_mceliece_348864f,
-    ("classic_mceliece") ClassicMcEliece460896: OQS_KEM_alg_classic_mceliece_460896,
-    ("classic_mceliece") ClassicMcEliece460896f: OQS_KEM_alg_classic_mceliece_460896f,
-    ("classic_mceliece") ClassicMcEliece6688128: OQS_KEM_alg_classic_mceliece_6688128,
-    ("classic_mceliece") ClassicMcEliece6688128f: OQS_KEM_alg_classic_mceliece_6688128f,
-    ("classic_mceliece") ClassicMcEliece6960119: OQS_KEM_alg_classic_mceliece_6960119,
-    ("classic_mceliece") ClassicMcEliece6960119f: OQS_KEM_alg_classic_mceliece_6960119f,
-    ("classic_mceliece") ClassicMcEliece8192128: OQS_KEM_alg_classic_mceliece_8192128,
-    ("classic_mceliece") ClassicMcEliece8192128f: OQS_KEM_alg_classic_mceliece_8192128f,
-    ("hqc") Hqc128: OQS_KEM_alg_hqc_128,
-    ("hqc") Hqc192: OQS_KEM_alg_hqc_192,
-    ("hqc") Hqc256: OQS_KEM_alg_hqc_256,
-    ("kyber") Kyber512: OQS_KEM_alg_kyber_512,
-    ("kyber") Kyber768: OQS_KEM_alg_kyber_768,
-    ("kyber") Kyber1024: OQS_KEM_alg_kyber_1024,
-    ("ml_kem") MlKem512: OQS_KEM_alg_ml_kem_512,
-    ("ml_kem") MlKem768: OQS_KEM_alg_ml_kem_768,
-    ("ml_kem") MlKem1024: OQS_KEM_alg_ml_kem_1024,
-    ("ntruprime") NtruPrimeSntrup761: OQS_KEM_alg_ntruprime_sntrup761,
-    ("frodokem") FrodoKem640Aes: OQS_KEM_alg_frodokem_640_aes,
-    ("frodokem") FrodoKem640Shake: OQS_KEM_alg_frodokem_640_shake,
-    ("frodokem") FrodoKem976Aes: OQS_KEM_alg_frodokem_976_aes,
-    ("frodokem") FrodoKem976Shake: OQS_KEM_alg_frodokem_976_shake,
-    ("frodokem") FrodoKem1344Aes: OQS_KEM_alg_frodokem_1344_aes,
-    ("frodokem") FrodoKem1344Shake: OQS_KEM_alg_frodokem_1344_shake,
+    ("bike") BikeL1: bike_l1,
+    ("bike") BikeL3: bike_l3,
+    ("bike") BikeL5: bike_l5,
+    ("classic_mceliece") ClassicMcEliece348864: classic_mceliece_348864,
+    ("classic_mceliece") ClassicMcEliece348864f: classic_mceliece_348864f,
+    ("classic_mceliece") ClassicMcEliece460896: classic_mceliece_460896,
+    ("classic_mceliece") ClassicMcEliece460896f: classic_mceliece_460896f,
+    ("classic_mceliece") ClassicMcEliece6688128: classic_mceliece_6688128,
+    ("classic_mceliece") ClassicMcEliece6688128f: classic_mceliece_6688128f,
+    ("classic_mceliece") ClassicMcEliece6960119: classic_mceliece_6960119,
+    ("classic_mceliece") ClassicMcEliece6960119f: classic_mceliece_6960119f,
+    ("classic_mceliece") ClassicMcEliece8192128: classic_mceliece_8192128,
+    ("classic_mceliece") ClassicMcEliece8192128f: classic_mceliece_8192128f,
+    ("hqc") Hqc128: hqc_128,
+    ("hqc") Hqc192: hqc_192,
+    ("hqc") Hqc256: hqc_256,
+    ("kyber") Kyber512: kyber_512,
+    ("kyber") Kyber768: kyber_768,
+    ("kyber") Kyber1024: kyber_1024,
+    ("ml_kem") MlKem512: ml_kem_512,
+    ("ml_kem") MlKem768: ml_kem_768,
+    ("ml_kem") MlKem1024: ml_kem_1024,
+    ("ntruprime") NtruPrimeSntrup761: ntruprime_sntrup761,
+    ("frodokem") FrodoKem640Aes: frodokem_640_aes,
+    ("frodokem") FrodoKem640Shake: frodokem_640_shake,
+    ("frodokem") FrodoKem976Aes: frodokem_976_aes,
+    ("frodokem") FrodoKem976Shake: frodokem_976_shake,
+    ("frodokem") FrodoKem1344Aes: frodokem_1344_aes,
+    ("frodokem") FrodoKem1344Shake: frodokem_1344_shake,
 }
 
 impl Algorithm {
@@ -198,6 +419,198 @@ impl Algorithm {
         let id = unsafe { CStr::from_ptr(self.to_id()) };
         id.to_str().expect("OQS algorithm names must be UTF-8")
     }
+
+    /// Iterate over all algorithms, including those that are not enabled
+    pub fn iter() -> impl Iterator<Item = Algorithm> {
+        Self::ALL.iter().copied()
+    }
+
+    /// Iterate over the algorithms that are enabled in the linked version of liboqs
+    pub fn enabled() -> impl Iterator<Item = Algorithm> {
+        Self::iter().filter(|alg| alg.is_enabled())
+    }
+
+    /// An estimate of the implementation liboqs selects for this algorithm on this CPU
+    ///
+    /// See [`cpu::summary`](crate::cpu::summary).
+    pub fn implementation(self) -> crate::cpu::Implementation {
+        crate::cpu::select(algorithm_enable_macro(self))
+    }
+
+    /// Object identifier of the algorithm, if one has been assigned
+    ///
+    /// These are the NIST OIDs of ML-KEM.
+    pub fn oid(self) -> Option<ObjectIdentifier> {
+        let oid = match self {
+            Algorithm::MlKem512 => "2.16.840.1.101.3.4.4.1",
+            Algorithm::MlKem768 => "2.16.840.1.101.3.4.4.2",
+            Algorithm::MlKem1024 => "2.16.840.1.101.3.4.4.3",
+            _ => return None,
+        };
+        Some(ObjectIdentifier::new_unwrap(oid))
+    }
+
+    /// Look up the algorithm with the object identifier `oid`
+    pub fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        Self::iter().find(|alg| alg.oid() == Some(oid))
+    }
+}
+
+/// Parse an algorithm from its liboqs identifier, such as `"ML-KEM-768"`
+impl core::str::FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        Self::iter()
+            .find(|alg| alg.name() == name)
+            .ok_or(Error::UnknownAlgorithm)
+    }
+}
+
+/// Family of a KEM algorithm
+///
+/// Optional support for `serde` if that feature is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Family {
+    /// BIKE
+    Bike,
+    /// Classic McEliece
+    ClassicMcEliece,
+    /// FrodoKEM
+    FrodoKem,
+    /// HQC
+    Hqc,
+    /// CRYSTALS-Kyber
+    Kyber,
+    /// ML-KEM (FIPS 203)
+    MlKem,
+    /// NTRU Prime
+    NtruPrime,
+}
+
+/// Properties of a KEM algorithm that are known without a [`Kem`]
+///
+/// See [`Algorithm::info`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct AlgorithmInfo {
+    /// Family of the algorithm
+    pub family: Family,
+    /// Standardization status of the algorithm
+    pub standardization: Standardization,
+    /// NIST security level claimed by the algorithm
+    pub claimed_nist_level: u8,
+    /// Is the algorithm IND-CCA secure
+    pub is_ind_cca: bool,
+    /// Length of a public key
+    pub length_public_key: usize,
+    /// Length of a secret key
+    pub length_secret_key: usize,
+    /// Length of a ciphertext
+    pub length_ciphertext: usize,
+    /// Length of a shared secret
+    pub length_shared_secret: usize,
+}
+
+impl Algorithm {
+    /// Returns the properties of the algorithm
+    ///
+    /// This does not need the algorithm to be enabled, and can be used in constants:
+    /// ```rust
+    /// use oqs::kem::{Algorithm, AlgorithmInfo};
+    /// const INFO: AlgorithmInfo = Algorithm::MlKem768.info();
+    /// assert_eq!(INFO.length_ciphertext, 1088);
+    /// ```
+    pub const fn info(self) -> AlgorithmInfo {
+        macro_rules! info {
+            ($family: ident, $status: ident, $level: literal, $ind_cca: literal,
+             $pk: literal, $sk: literal, $ct: literal, $ss: literal) => {
+                AlgorithmInfo {
+                    family: Family::$family,
+                    standardization: Standardization::$status,
+                    claimed_nist_level: $level,
+                    is_ind_cca: $ind_cca,
+                    length_public_key: $pk,
+                    length_secret_key: $sk,
+                    length_ciphertext: $ct,
+                    length_shared_secret: $ss,
+                }
+            };
+        }
+        match self {
+            Algorithm::BikeL1 => info!(Bike, Experimental, 1, false, 1541, 5223, 1573, 32),
+            Algorithm::BikeL3 => info!(Bike, Experimental, 3, false, 3083, 10105, 3115, 32),
+            Algorithm::BikeL5 => info!(Bike, Experimental, 5, false, 5122, 16494, 5154, 32),
+            Algorithm::ClassicMcEliece348864 => {
+                info!(ClassicMcEliece, Experimental, 1, true, 261120, 6492, 96, 32)
+            }
+            Algorithm::ClassicMcEliece348864f => {
+                info!(ClassicMcEliece, Experimental, 1, true, 261120, 6492, 96, 32)
+            }
+            Algorithm::ClassicMcEliece460896 => info!(
+                ClassicMcEliece,
+                Experimental, 3, true, 524160, 13608, 156, 32
+            ),
+            Algorithm::ClassicMcEliece460896f => info!(
+                ClassicMcEliece,
+                Experimental, 3, true, 524160, 13608, 156, 32
+            ),
+            Algorithm::ClassicMcEliece6688128 => info!(
+                ClassicMcEliece,
+                Experimental, 5, true, 1044992, 13932, 208, 32
+            ),
+            Algorithm::ClassicMcEliece6688128f => info!(
+                ClassicMcEliece,
+                Experimental, 5, true, 1044992, 13932, 208, 32
+            ),
+            Algorithm::ClassicMcEliece6960119 => info!(
+                ClassicMcEliece,
+                Experimental, 5, true, 1047319, 13948, 194, 32
+            ),
+            Algorithm::ClassicMcEliece6960119f => info!(
+                ClassicMcEliece,
+                Experimental, 5, true, 1047319, 13948, 194, 32
+            ),
+            Algorithm::ClassicMcEliece8192128 => info!(
+                ClassicMcEliece,
+                Experimental, 5, true, 1357824, 14120, 208, 32
+            ),
+            Algorithm::ClassicMcEliece8192128f => info!(
+                ClassicMcEliece,
+                Experimental, 5, true, 1357824, 14120, 208, 32
+            ),
+            Algorithm::Hqc128 => info!(Hqc, Draft, 1, true, 2249, 2305, 4433, 64),
+            Algorithm::Hqc192 => info!(Hqc, Draft, 3, true, 4522, 4586, 8978, 64),
+            Algorithm::Hqc256 => info!(Hqc, Draft, 5, true, 7245, 7317, 14421, 64),
+            Algorithm::Kyber512 => info!(Kyber, Deprecated, 1, true, 800, 1632, 768, 32),
+            Algorithm::Kyber768 => info!(Kyber, Deprecated, 3, true, 1184, 2400, 1088, 32),
+            Algorithm::Kyber1024 => info!(Kyber, Deprecated, 5, true, 1568, 3168, 1568, 32),
+            Algorithm::MlKem512 => info!(MlKem, Standardized, 1, true, 800, 1632, 768, 32),
+            Algorithm::MlKem768 => info!(MlKem, Standardized, 3, true, 1184, 2400, 1088, 32),
+            Algorithm::MlKem1024 => info!(MlKem, Standardized, 5, true, 1568, 3168, 1568, 32),
+            Algorithm::NtruPrimeSntrup761 => {
+                info!(NtruPrime, Experimental, 2, true, 1158, 1763, 1039, 32)
+            }
+            Algorithm::FrodoKem640Aes => {
+                info!(FrodoKem, Experimental, 1, true, 9616, 19888, 9720, 16)
+            }
+            Algorithm::FrodoKem640Shake => {
+                info!(FrodoKem, Experimental, 1, true, 9616, 19888, 9720, 16)
+            }
+            Algorithm::FrodoKem976Aes => {
+                info!(FrodoKem, Experimental, 3, true, 15632, 31296, 15744, 24)
+            }
+            Algorithm::FrodoKem976Shake => {
+                info!(FrodoKem, Experimental, 3, true, 15632, 31296, 15744, 24)
+            }
+            Algorithm::FrodoKem1344Aes => {
+                info!(FrodoKem, Experimental, 5, true, 21520, 43088, 21632, 32)
+            }
+            Algorithm::FrodoKem1344Shake => {
+                info!(FrodoKem, Experimental, 5, true, 21520, 43088, 21632, 32)
+            }
+        }
+    }
 }
 
 #[cfg(feature = "std")]
@@ -307,6 +720,14 @@ impl Kem {
         kem.length_keypair_seed
     }
 
+    /// Does this algorithm support generating keypairs from a seed?
+    ///
+    /// If not, [`keypair_derand`](Kem::keypair_derand) fails with [`Error::Unsupported`].
+    pub fn supports_derand(&self) -> bool {
+        let kem = unsafe { self.kem.as_ref() };
+        kem.keypair_derand.is_some() && kem.length_keypair_seed > 0
+    }
+
     /// Obtain a secret key objects from bytes
     ///
     /// Returns None if the secret key is not the correct length.
@@ -365,7 +786,7 @@ impl Kem {
     /// Generate a new keypair
     pub fn keypair(&self) -> Result<(PublicKey, SecretKey)> {
         let kem = unsafe { self.kem.as_ref() };
-        let func = kem.keypair.unwrap();
+        let func = kem.keypair.ok_or(Error::Unsupported)?;
         let mut pk = PublicKey {
             bytes: Vec::with_capacity(kem.length_public_key),
         };
@@ -373,7 +794,7 @@ impl Kem {
             bytes: Vec::with_capacity(kem.length_secret_key),
         };
         let status = unsafe { func(pk.bytes.as_mut_ptr(), sk.bytes.as_mut_ptr()) };
-        status_to_result(status)?;
+        self.check_status(Operation::Keypair, status)?;
         // update the lengths of the vecs
         // this is safe to do, as we have initialised them now.
         unsafe {
@@ -389,11 +810,12 @@ impl Kem {
         seed: S,
     ) -> Result<(PublicKey, SecretKey)> {
         let seed = seed.into();
-        if seed.bytes.len() != self.length_keypair_seed() {
-            return Err(Error::InvalidLength);
+        if !self.supports_derand() {
+            return Err(Error::Unsupported);
         }
+        self.check_length(Operation::Keypair, self.length_keypair_seed(), seed.len())?;
         let kem = unsafe { self.kem.as_ref() };
-        let func = kem.keypair_derand.unwrap();
+        let func = kem.keypair_derand.ok_or(Error::Unsupported)?;
         let mut pk = PublicKey {
             bytes: Vec::with_capacity(kem.length_public_key),
         };
@@ -407,7 +829,7 @@ impl Kem {
                 seed.bytes.as_ptr(),
             )
         };
-        status_to_result(status)?;
+        self.check_status(Operation::Keypair, status)?;
         // update the lengths of the vecs
         // this is safe to do, as we have initialised them now.
         unsafe {
@@ -423,11 +845,9 @@ impl Kem {
         pk: P,
     ) -> Result<(Ciphertext, SharedSecret)> {
         let pk = pk.into();
-        if pk.bytes.len() != self.length_public_key() {
-            return Err(Error::InvalidLength);
-        }
+        self.check_length(Operation::Encapsulate, self.length_public_key(), pk.len())?;
         let kem = unsafe { self.kem.as_ref() };
-        let func = kem.encaps.unwrap();
+        let func = kem.encaps.ok_or(Error::Unsupported)?;
         let mut ct = Ciphertext {
             bytes: Vec::with_capacity(kem.length_ciphertext),
         };
@@ -442,7 +862,7 @@ impl Kem {
                 pk.bytes.as_ptr(),
             )
         };
-        status_to_result(status)?;
+        self.check_status(Operation::Encapsulate, status)?;
         // update the lengths of the vecs
         // this is safe to do, as we have initialised them now.
         unsafe {
@@ -461,20 +881,556 @@ impl Kem {
         let kem = unsafe { self.kem.as_ref() };
         let sk = sk.into();
         let ct = ct.into();
-        if sk.bytes.len() != self.length_secret_key() || ct.bytes.len() != self.length_ciphertext()
-        {
-            return Err(Error::InvalidLength);
-        }
+        self.check_length(Operation::Decapsulate, self.length_secret_key(), sk.len())?;
+        self.check_length(Operation::Decapsulate, self.length_ciphertext(), ct.len())?;
         let mut ss = SharedSecret {
             bytes: Vec::with_capacity(kem.length_shared_secret),
         };
-        let func = kem.decaps.unwrap();
+        let func = kem.decaps.ok_or(Error::Unsupported)?;
         // Call decapsulate
         let status = unsafe { func(ss.bytes.as_mut_ptr(), ct.bytes.as_ptr(), sk.bytes.as_ptr()) };
-        status_to_result(status)?;
+        self.check_status(Operation::Decapsulate, status)?;
         // update the lengths of the vecs
         // this is safe to do, as we have initialised them now.
         unsafe { ss.bytes.set_len(kem.length_shared_secret) };
         Ok(ss)
     }
+
+    /// Generate a new keypair into the provided buffers
+    ///
+    /// The key is written to the start of `pk` and `sk`, which must hold at least
+    /// [`length_public_key`](Kem::length_public_key) and
+    /// [`length_secret_key`](Kem::length_secret_key) bytes.
+    pub fn keypair_into(&self, pk: &mut [u8], sk: &mut [u8]) -> Result<()> {
+        self.check_capacity(Operation::Keypair, self.length_public_key(), pk.len())?;
+        self.check_capacity(Operation::Keypair, self.length_secret_key(), sk.len())?;
+        let kem = unsafe { self.kem.as_ref() };
+        let func = kem.keypair.ok_or(Error::Unsupported)?;
+        let status = unsafe { func(pk.as_mut_ptr(), sk.as_mut_ptr()) };
+        self.check_status(Operation::Keypair, status)
+    }
+
+    /// Encapsulate to the provided public key, writing into the provided buffers
+    ///
+    /// The ciphertext and shared secret are written to the start of `ct` and `ss`, which must hold at least
+    /// [`length_ciphertext`](Kem::length_ciphertext) and
+    /// [`length_shared_secret`](Kem::length_shared_secret) bytes.
+    pub fn encapsulate_into<'a, P: Into<PublicKeyRef<'a>>>(
+        &self,
+        pk: P,
+        ct: &mut [u8],
+        ss: &mut [u8],
+    ) -> Result<()> {
+        let pk = pk.into();
+        self.check_length(Operation::Encapsulate, self.length_public_key(), pk.len())?;
+        self.check_capacity(Operation::Encapsulate, self.length_ciphertext(), ct.len())?;
+        self.check_capacity(
+            Operation::Encapsulate,
+            self.length_shared_secret(),
+            ss.len(),
+        )?;
+        let kem = unsafe { self.kem.as_ref() };
+        let func = kem.encaps.ok_or(Error::Unsupported)?;
+        let status = unsafe { func(ct.as_mut_ptr(), ss.as_mut_ptr(), pk.bytes.as_ptr()) };
+        self.check_status(Operation::Encapsulate, status)
+    }
+
+    /// Decapsulate the provided ciphertext, writing into the provided buffer
+    ///
+    /// The shared secret is written to the start of `ss`, which must hold at least
+    /// [`length_shared_secret`](Kem::length_shared_secret) bytes.
+    pub fn decapsulate_into<'a, 'b, S: Into<SecretKeyRef<'a>>, C: Into<CiphertextRef<'b>>>(
+        &self,
+        sk: S,
+        ct: C,
+        ss: &mut [u8],
+    ) -> Result<()> {
+        let sk = sk.into();
+        let ct = ct.into();
+        self.check_length(Operation::Decapsulate, self.length_secret_key(), sk.len())?;
+        self.check_length(Operation::Decapsulate, self.length_ciphertext(), ct.len())?;
+        self.check_capacity(
+            Operation::Decapsulate,
+            self.length_shared_secret(),
+            ss.len(),
+        )?;
+        let kem = unsafe { self.kem.as_ref() };
+        let func = kem.decaps.ok_or(Error::Unsupported)?;
+        let status = unsafe { func(ss.as_mut_ptr(), ct.bytes.as_ptr(), sk.bytes.as_ptr()) };
+        self.check_status(Operation::Decapsulate, status)
+    }
+
+    /// Fails with [`Error::WrongLength`] if an input of `operation` is not `expected` bytes long
+    fn check_length(&self, operation: Operation, expected: usize, actual: usize) -> Result<()> {
+        if actual != expected {
+            return Err(self.wrong_length(operation, expected, actual));
+        }
+        Ok(())
+    }
+
+    /// Fails with [`Error::WrongLength`] if an output buffer of `operation` is too short
+    fn check_capacity(&self, operation: Operation, expected: usize, actual: usize) -> Result<()> {
+        if actual < expected {
+            return Err(self.wrong_length(operation, expected, actual));
+        }
+        Ok(())
+    }
+
+    fn wrong_length(&self, operation: Operation, expected: usize, actual: usize) -> Error {
+        Error::WrongLength {
+            operation,
+            algorithm: self.algorithm.name(),
+            expected,
+            actual,
+        }
+    }
+
+    /// Convert the status returned by liboqs for `operation`
+    fn check_status(&self, operation: Operation, status: OQS_STATUS) -> Result<()> {
+        status_to_result(status)
+            .map_err(|error| error.in_operation(operation, self.algorithm.name()))
+    }
 }
+
+/// Encoding of keys as DER and PEM documents
+#[cfg(feature = "pkcs8")]
+mod pkcs8_encoding {
+    use alloc::string::String;
+
+    use pkcs8::der::zeroize::Zeroizing;
+    use pkcs8::{Document, LineEnding, SecretDocument};
+
+    use super::*;
+    use crate::encoding::{self, DecodedSecretKey, PrivateKeyFormat};
+
+    /// Encoding of keys as DER and PEM documents
+    ///
+    /// Public keys are encoded as `SubjectPublicKeyInfo` and secret keys as PKCS#8,
+    /// identified by [`Algorithm::oid`]. For ML-KEM, the secret key uses the `expandedKey`
+    /// format of the IETF LAMPS draft; keys encoded as seed are also accepted.
+    ///
+    /// Algorithms without an OID fail with [`Error::Unsupported`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # if !cfg!(feature = "ml_kem") { return; }
+    /// use oqs::kem::{Algorithm, Kem};
+    /// oqs::init();
+    /// let kem = Kem::new(Algorithm::MlKem768).unwrap();
+    /// let (pk, sk) = kem.keypair().unwrap();
+    /// let pem = kem.public_key_to_pem(&pk, pkcs8::LineEnding::LF).unwrap();
+    /// assert_eq!(kem.public_key_from_pem(&pem).unwrap(), pk);
+    /// let der = kem.secret_key_to_der(&sk, &pk).unwrap();
+    /// assert_eq!(kem.secret_key_from_der(der.as_bytes()).unwrap(), sk);
+    /// ```
+    impl Kem {
+        fn oid(&self) -> Result<ObjectIdentifier> {
+            self.algorithm.oid().ok_or(Error::Unsupported)
+        }
+
+        fn lengths(&self) -> (usize, usize) {
+            (self.length_secret_key(), self.length_public_key())
+        }
+
+        fn check_keys(&self, sk: &[u8], pk: &[u8]) -> Result<()> {
+            self.check_length(Operation::Encode, self.length_secret_key(), sk.len())?;
+            self.check_length(Operation::Encode, self.length_public_key(), pk.len())
+        }
+
+        fn decoded_secret_key(&self, decoded: DecodedSecretKey<'_>) -> Result<SecretKey> {
+            match decoded {
+                DecodedSecretKey::Expanded(sk) => Ok(SecretKeyRef::new(sk).to_owned()),
+                DecodedSecretKey::Seed(seed) => {
+                    self.check_length(Operation::Decode, self.length_keypair_seed(), seed.len())?;
+                    Ok(self.keypair_derand(KeypairSeedRef::new(seed))?.1)
+                }
+            }
+        }
+
+        /// Encode a public key as DER `SubjectPublicKeyInfo`
+        pub fn public_key_to_der<'a>(&self, pk: impl Into<PublicKeyRef<'a>>) -> Result<Document> {
+            let pk = pk.into();
+            self.check_length(Operation::Encode, self.length_public_key(), pk.len())?;
+            encoding::public_key_to_der(self.oid()?, &pk)
+        }
+
+        /// Encode a public key as PEM `SubjectPublicKeyInfo`
+        pub fn public_key_to_pem<'a>(
+            &self,
+            pk: impl Into<PublicKeyRef<'a>>,
+            line_ending: LineEnding,
+        ) -> Result<String> {
+            let pk = pk.into();
+            self.check_length(Operation::Encode, self.length_public_key(), pk.len())?;
+            encoding::public_key_to_pem(self.oid()?, &pk, line_ending)
+        }
+
+        /// Decode a public key from DER `SubjectPublicKeyInfo`
+        ///
+        /// Fails with [`Error::AlgorithmMismatch`] if the key belongs to another algorithm.
+        pub fn public_key_from_der(&self, der: &[u8]) -> Result<PublicKey> {
+            let bytes = encoding::public_key_from_der(
+                self.oid()?,
+                self.algorithm.name(),
+                der,
+                self.length_public_key(),
+            )?;
+            Ok(PublicKey { bytes })
+        }
+
+        /// Decode a public key from PEM `SubjectPublicKeyInfo`
+        ///
+        /// Fails with [`Error::AlgorithmMismatch`] if the key belongs to another algorithm.
+        pub fn public_key_from_pem(&self, pem: &str) -> Result<PublicKey> {
+            let bytes = encoding::public_key_from_pem(
+                self.oid()?,
+                self.algorithm.name(),
+                pem,
+                self.length_public_key(),
+            )?;
+            Ok(PublicKey { bytes })
+        }
+
+        /// Encode a secret key as DER PKCS#8
+        pub fn secret_key_to_der<'a, 'b>(
+            &self,
+            sk: impl Into<SecretKeyRef<'a>>,
+            pk: impl Into<PublicKeyRef<'b>>,
+        ) -> Result<SecretDocument> {
+            let (sk, pk) = (sk.into(), pk.into());
+            self.check_keys(&sk, &pk)?;
+            encoding::secret_key_to_der(self.oid()?, PrivateKeyFormat::Ietf, &sk, &pk)
+        }
+
+        /// Encode a secret key as PEM PKCS#8
+        pub fn secret_key_to_pem<'a, 'b>(
+            &self,
+            sk: impl Into<SecretKeyRef<'a>>,
+            pk: impl Into<PublicKeyRef<'b>>,
+            line_ending: LineEnding,
+        ) -> Result<Zeroizing<String>> {
+            let (sk, pk) = (sk.into(), pk.into());
+            self.check_keys(&sk, &pk)?;
+            encoding::secret_key_to_pem(self.oid()?, PrivateKeyFormat::Ietf, &sk, &pk, line_ending)
+        }
+
+        /// Decode a secret key from DER PKCS#8
+        ///
+        /// Fails with [`Error::AlgorithmMismatch`] if the key belongs to another algorithm.
+        pub fn secret_key_from_der(&self, der: &[u8]) -> Result<SecretKey> {
+            encoding::secret_key_from_der(
+                self.oid()?,
+                self.algorithm.name(),
+                PrivateKeyFormat::Ietf,
+                der,
+                self.lengths(),
+                |decoded| self.decoded_secret_key(decoded),
+            )
+        }
+
+        /// Decode a secret key from PEM PKCS#8
+        ///
+        /// Fails with [`Error::AlgorithmMismatch`] if the key belongs to another algorithm.
+        pub fn secret_key_from_pem(&self, pem: &str) -> Result<SecretKey> {
+            encoding::secret_key_from_pem(
+                self.oid()?,
+                self.algorithm.name(),
+                PrivateKeyFormat::Ietf,
+                pem,
+                self.lengths(),
+                |decoded| self.decoded_secret_key(decoded),
+            )
+        }
+    }
+}
+
+/// Statically-typed KEM API
+///
+/// Every algorithm has a marker type, like [`MlKem768`](super::MlKem768), that implements
+/// [`KemAlgorithm`]. The buffers in this module are generic over that marker type and
+/// have the sizes of the algorithm, so that mixing up keys of different algorithms
+/// is a compile error.
+///
+/// The typed buffers can also be passed to the methods of [`Kem`].
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "ml_kem")] {
+/// use oqs::kem::{KemAlgorithm, MlKem768};
+/// oqs::init();
+/// let (pk, sk) = MlKem768::keypair().unwrap();
+/// let (ct, ss) = MlKem768::encapsulate(&pk).unwrap();
+/// let ss2 = MlKem768::decapsulate(&sk, &ct).unwrap();
+/// assert_eq!(ss, ss2);
+/// let array: &[u8; 1088] = ct.as_array();
+/// # }
+/// ```
+pub mod typed {
+    use super::*;
+    use crate::newtype_array;
+
+    newtype_array!(PublicKey, KemAlgorithm::PublicKeyBytes);
+    newtype_array!(secret SecretKey, KemAlgorithm::SecretKeyBytes);
+    newtype_array!(Ciphertext, KemAlgorithm::CiphertextBytes);
+    newtype_array!(secret SharedSecret, KemAlgorithm::SharedSecretBytes);
+
+    /// KEM algorithm that is known at compile time
+    ///
+    /// The associated types are the `[u8; N]` arrays that hold the buffers of the algorithm.
+    pub trait KemAlgorithm:
+        Clone + Copy + core::fmt::Debug + Default + Eq + Send + Sync + 'static
+    {
+        /// The algorithm
+        const ALGORITHM: Algorithm;
+        /// Array holding a public key
+        type PublicKeyBytes: ByteArray;
+        /// Array holding a secret key
+        type SecretKeyBytes: ByteArray;
+        /// Array holding a ciphertext
+        type CiphertextBytes: ByteArray;
+        /// Array holding a shared secret
+        type SharedSecretBytes: ByteArray;
+
+        /// Generate a new keypair
+        fn keypair() -> Result<(PublicKey<Self>, SecretKey<Self>)> {
+            let kem = Kem::new(Self::ALGORITHM)?;
+            let mut pk = PublicKey::<Self>::zeroed();
+            let mut sk = SecretKey::<Self>::zeroed();
+            kem.keypair_into(pk.bytes.as_mut_slice(), sk.bytes.as_mut_slice())?;
+            Ok((pk, sk))
+        }
+
+        /// Encapsulate to the provided public key
+        fn encapsulate(pk: &PublicKey<Self>) -> Result<(Ciphertext<Self>, SharedSecret<Self>)> {
+            let kem = Kem::new(Self::ALGORITHM)?;
+            let mut ct = Ciphertext::<Self>::zeroed();
+            let mut ss = SharedSecret::<Self>::zeroed();
+            kem.encapsulate_into(pk, ct.bytes.as_mut_slice(), ss.bytes.as_mut_slice())?;
+            Ok((ct, ss))
+        }
+
+        /// Decapsulate the provided ciphertext
+        fn decapsulate(sk: &SecretKey<Self>, ct: &Ciphertext<Self>) -> Result<SharedSecret<Self>> {
+            let kem = Kem::new(Self::ALGORITHM)?;
+            let mut ss = SharedSecret::<Self>::zeroed();
+            kem.decapsulate_into(sk, ct, ss.bytes.as_mut_slice())?;
+            Ok(ss)
+        }
+    }
+
+    impl<'a, A: KemAlgorithm> From<&'a PublicKey<A>> for PublicKeyRef<'a> {
+        fn from(pk: &'a PublicKey<A>) -> PublicKeyRef<'a> {
+            PublicKeyRef::new(pk.as_ref())
+        }
+    }
+
+    impl<'a, A: KemAlgorithm> From<&'a SecretKey<A>> for SecretKeyRef<'a> {
+        fn from(sk: &'a SecretKey<A>) -> SecretKeyRef<'a> {
+            SecretKeyRef::new(sk.as_ref())
+        }
+    }
+
+    impl<'a, A: KemAlgorithm> From<&'a Ciphertext<A>> for CiphertextRef<'a> {
+        fn from(ct: &'a Ciphertext<A>) -> CiphertextRef<'a> {
+            CiphertextRef::new(ct.as_ref())
+        }
+    }
+
+    impl<'a, A: KemAlgorithm> From<&'a SharedSecret<A>> for SharedSecretRef<'a> {
+        fn from(ss: &'a SharedSecret<A>) -> SharedSecretRef<'a> {
+            SharedSecretRef::new(ss.as_ref())
+        }
+    }
+}
+
+pub use typed::KemAlgorithm;
+
+/// Adapters for the RustCrypto [`kem`](::kem) traits
+///
+/// liboqs has no derandomized encapsulation, so
+/// [`Encapsulate::encapsulate`](::kem::Encapsulate::encapsulate) ignores the RNG it is given
+/// and draws from the generator liboqs uses, see [`crate::rand`].
+/// [`DecapsulationKey::generate`] only uses its RNG for algorithms that
+/// [support derandomized key generation](Kem::supports_derand).
+#[cfg(feature = "kem")]
+mod kem_traits {
+    use alloc::sync::Arc;
+
+    use rand_core::CryptoRngCore;
+
+    use super::*;
+
+    /// Encapsulation key implementing [`Encapsulate`](::kem::Encapsulate)
+    ///
+    /// Encapsulation returns any ciphertext and shared secret types that can be
+    /// converted from [`Ciphertext`] and [`SharedSecret`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # if !cfg!(feature = "ml_kem") { return; }
+    /// use kem::{Decapsulate, Encapsulate};
+    /// use oqs::kem::{Algorithm, Ciphertext, DecapsulationKey, SharedSecret};
+    /// let mut rng = ::rand::rngs::OsRng;
+    /// oqs::init();
+    /// let dk = DecapsulationKey::generate(Algorithm::MlKem768, &mut rng).unwrap();
+    /// let (ct, ss): (Ciphertext, SharedSecret) = dk.encapsulation_key().encapsulate(&mut rng).unwrap();
+    /// let ss2: SharedSecret = dk.decapsulate(&ct).unwrap();
+    /// assert_eq!(ss, ss2);
+    /// ```
+    #[derive(Clone)]
+    pub struct EncapsulationKey {
+        kem: Arc<Kem>,
+        pk: PublicKey,
+    }
+
+    /// Decapsulation key implementing [`Decapsulate`](::kem::Decapsulate)
+    ///
+    /// Decapsulation accepts any ciphertext type that can be viewed as bytes,
+    /// and returns any shared secret type that can be converted from [`SharedSecret`].
+    pub struct DecapsulationKey {
+        sk: SecretKey,
+        encapsulation_key: EncapsulationKey,
+    }
+
+    impl EncapsulationKey {
+        /// Construct an encapsulation key from a public key
+        ///
+        /// Fails with [`Error::WrongLength`] if the key does not fit the algorithm of `kem`.
+        pub fn new(kem: Kem, pk: PublicKey) -> Result<Self> {
+            kem.check_length(Operation::Encapsulate, kem.length_public_key(), pk.len())?;
+            Ok(Self {
+                kem: Arc::new(kem),
+                pk,
+            })
+        }
+
+        /// Construct an encapsulation key from the bytes of a public key for `algorithm`
+        pub fn from_bytes(algorithm: Algorithm, pk: &[u8]) -> Result<Self> {
+            let kem = Kem::new(algorithm)?;
+            let pk = PublicKeyRef::new(pk).to_owned();
+            Self::new(kem, pk)
+        }
+
+        /// Get the algorithm of this key
+        pub fn algorithm(&self) -> Algorithm {
+            self.kem.algorithm()
+        }
+
+        /// Get the public key
+        pub fn public_key(&self) -> PublicKeyRef<'_> {
+            PublicKeyRef::from(&self.pk)
+        }
+    }
+
+    impl DecapsulationKey {
+        /// Generate a new keypair for `algorithm`
+        ///
+        /// Algorithms that [support derandomized key generation](Kem::supports_derand)
+        /// derive the keypair from a seed drawn from `rng`. All others ignore `rng`
+        /// and draw from the generator liboqs uses, see [`crate::rand`].
+        pub fn generate(algorithm: Algorithm, rng: &mut impl CryptoRngCore) -> Result<Self> {
+            let kem = Kem::new(algorithm)?;
+            let (pk, sk) = if kem.supports_derand() {
+                let mut seed = KeypairSeed {
+                    bytes: alloc::vec![0; kem.length_keypair_seed()],
+                };
+                rng.fill_bytes(&mut seed.bytes);
+                kem.keypair_derand(&seed)?
+            } else {
+                kem.keypair()?
+            };
+            Self::new(kem, pk, sk)
+        }
+
+        /// Construct a decapsulation key from an existing keypair
+        ///
+        /// Fails with [`Error::WrongLength`] if the keys do not fit the algorithm of `kem`.
+        pub fn new(kem: Kem, pk: PublicKey, sk: SecretKey) -> Result<Self> {
+            kem.check_length(Operation::Decapsulate, kem.length_secret_key(), sk.len())?;
+            Ok(Self {
+                sk,
+                encapsulation_key: EncapsulationKey::new(kem, pk)?,
+            })
+        }
+
+        /// Get the algorithm of this key
+        pub fn algorithm(&self) -> Algorithm {
+            self.encapsulation_key.algorithm()
+        }
+
+        /// Get the matching encapsulation key
+        pub fn encapsulation_key(&self) -> &EncapsulationKey {
+            &self.encapsulation_key
+        }
+
+        /// Get the secret key
+        pub fn secret_key(&self) -> SecretKeyRef<'_> {
+            SecretKeyRef::from(&self.sk)
+        }
+    }
+
+    impl core::fmt::Debug for EncapsulationKey {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("EncapsulationKey")
+                .field("algorithm", &self.algorithm())
+                .field("pk", &self.pk)
+                .finish()
+        }
+    }
+
+    impl core::fmt::Debug for DecapsulationKey {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("DecapsulationKey")
+                .field("algorithm", &self.algorithm())
+                .field("sk", &self.sk)
+                .finish()
+        }
+    }
+
+    impl PartialEq for EncapsulationKey {
+        fn eq(&self, other: &Self) -> bool {
+            self.algorithm() == other.algorithm() && self.pk == other.pk
+        }
+    }
+
+    impl Eq for EncapsulationKey {}
+
+    impl<EK, SS> ::kem::Encapsulate<EK, SS> for EncapsulationKey
+    where
+        EK: From<Ciphertext>,
+        SS: From<SharedSecret>,
+    {
+        type Error = Error;
+
+        /// Encapsulate to this key
+        ///
+        /// `_rng` is ignored, liboqs draws from its own generator.
+        fn encapsulate(&self, _rng: &mut impl CryptoRngCore) -> Result<(EK, SS)> {
+            let (ct, ss) = self.kem.encapsulate(&self.pk)?;
+            Ok((ct.into(), ss.into()))
+        }
+    }
+
+    impl<EK, SS> ::kem::Decapsulate<EK, SS> for DecapsulationKey
+    where
+        EK: AsRef<[u8]>,
+        SS: From<SharedSecret>,
+    {
+        type Error = Error;
+
+        fn decapsulate(&self, encapsulated_key: &EK) -> Result<SS> {
+            let kem = &self.encapsulation_key.kem;
+            let ct = CiphertextRef::new(encapsulated_key.as_ref());
+            Ok(kem.decapsulate(&self.sk, ct)?.into())
+        }
+    }
+
+    impl From<Ciphertext> for Vec<u8> {
+        fn from(ct: Ciphertext) -> Vec<u8> {
+            ct.into_vec()
+        }
+    }
+}
+
+#[cfg(feature = "kem")]
+pub use kem_traits::{DecapsulationKey, EncapsulationKey};
@@ -43,13 +43,32 @@
 //! ```
 // needs to be imported to be made available
 extern crate alloc;
+// the tests use `std` also without the `std` feature
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
 
 use ffi::common::OQS_STATUS;
 
 /// Access the OQS ffi through this crate.
 pub use oqs_sys as ffi;
 
+/// Object identifiers, as used by [`kem::Algorithm::from_oid`] and [`sig::Algorithm::from_oid`].
+pub use const_oid::ObjectIdentifier;
+#[doc(hidden)]
+pub use subtle;
+/// Constant-time equality, implemented for buffers holding secrets.
+pub use subtle::ConstantTimeEq;
+
+mod build_info;
+pub use build_info::{build_info, version, BuildInfo};
+#[cfg(feature = "pkcs8")]
+mod encoding;
+#[cfg(all(test, feature = "deterministic_rng", feature = "std"))]
+mod kat;
+#[cfg(any(feature = "rand_core", feature = "deterministic_rng"))]
+mod lock;
 mod macros;
+pub use macros::ByteArray;
 
 /// Initialize liboqs
 ///
@@ -76,6 +95,73 @@ pub fn init() {
     unsafe { ffi::common::OQS_init() };
 }
 
+/// Release the global resources of liboqs, such as the OpenSSL algorithms it fetched
+///
+/// Call this at the end of the program to keep leak checkers such as
+/// valgrind quiet. It does not release the per-thread state of the calling
+/// thread, see [`thread_stop`].
+///
+/// When the ``std`` feature is enabled, this method is thread-safe and
+/// only the first call has an effect.
+///
+/// # Safety
+///
+/// liboqs must not be used afterwards, from any thread, and no other
+/// thread may be using it during the call. This includes everything in this
+/// crate that calls into liboqs, and [`init`] does not make it usable again.
+#[cfg(feature = "std")]
+pub unsafe fn shutdown() {
+    use std::sync::Once;
+    static SHUTDOWN: Once = Once::new();
+    SHUTDOWN.call_once(|| {
+        unsafe { ffi::common::OQS_destroy() };
+    });
+}
+
+/// Release the global resources of liboqs, such as the OpenSSL algorithms it fetched
+///
+/// It does not release the per-thread state of the calling thread, see [`thread_stop`].
+///
+/// # Safety
+///
+/// liboqs must not be used afterwards, and this must only be called once.
+/// This ``no_std`` variant is not thread-safe.
+#[cfg(not(feature = "std"))]
+pub unsafe fn shutdown() {
+    unsafe { ffi::common::OQS_destroy() };
+}
+
+/// Release the state liboqs holds for the calling thread
+///
+/// If liboqs uses OpenSSL, this frees the thread-local state of OpenSSL.
+/// Call it before a thread that used liboqs exits, or use
+/// [`thread_stop_on_exit`] to do that automatically.
+/// The thread may keep using liboqs afterwards, which allocates the state again.
+pub fn thread_stop() {
+    unsafe { ffi::common::OQS_thread_stop() };
+}
+
+/// Call [`thread_stop`] when the calling thread exits
+///
+/// This is useful in the workers of thread pools. It can be called more than
+/// once per thread. Depending on the platform, it may not run for the main
+/// thread, as the process can exit before it does.
+#[cfg(feature = "std")]
+pub fn thread_stop_on_exit() {
+    struct ThreadStop;
+
+    impl Drop for ThreadStop {
+        fn drop(&mut self) {
+            thread_stop();
+        }
+    }
+
+    thread_local! {
+        static THREAD_STOP: ThreadStop = const { ThreadStop };
+    }
+    THREAD_STOP.with(|_| {});
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 /// Possible errors
@@ -88,7 +174,41 @@ pub enum Error {
     #[allow(clippy::upper_case_acronyms)]
     ErrorExternalOpenSSL,
     /// Invalid length of a public object
+    #[deprecated(note = "lengths are reported as `Error::WrongLength`")]
     InvalidLength,
+    /// A key was used with a different algorithm than it was made for
+    AlgorithmMismatch,
+    /// The algorithm does not support this operation
+    Unsupported,
+    /// A DER or PEM document could not be decoded
+    InvalidEncoding,
+    /// An algorithm name was not recognized
+    UnknownAlgorithm,
+    /// A buffer or key passed to an operation has the wrong length
+    WrongLength {
+        /// The operation
+        operation: Operation,
+        /// Name of the algorithm
+        algorithm: &'static str,
+        /// Length the operation needs, which is the maximum length for signatures
+        /// and context strings, and the minimum length for output buffers,
+        /// pre-shared keys and composite keys and signatures
+        expected: usize,
+        /// Length of the buffer
+        actual: usize,
+    },
+    /// liboqs failed to carry out a KEM or signature operation
+    Failed {
+        /// The operation
+        operation: Operation,
+        /// Name of the algorithm
+        algorithm: &'static str,
+    },
+    /// A signature is not valid for the message and public key
+    VerificationFailed {
+        /// Name of the algorithm
+        algorithm: &'static str,
+    },
 }
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
@@ -100,12 +220,117 @@ impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::AlgorithmDisabled => write!(f, "OQS Error: Algorithm has been disabled"),
+            Error::Error => write!(f, "OQS Error: Operation failed"),
             Error::ErrorExternalOpenSSL => write!(f, "OQS error: OpenSSL call failed"),
-            _ => write!(f, "OQS Error!"),
+            #[allow(deprecated)]
+            Error::InvalidLength => write!(f, "OQS Error: Invalid length"),
+            Error::AlgorithmMismatch => {
+                write!(f, "OQS Error: Key belongs to a different algorithm")
+            }
+            Error::Unsupported => write!(f, "OQS Error: Not supported by this algorithm"),
+            Error::InvalidEncoding => write!(f, "OQS Error: Invalid DER or PEM encoding"),
+            Error::UnknownAlgorithm => write!(f, "OQS Error: Unknown algorithm name"),
+            Error::WrongLength {
+                operation,
+                algorithm,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "OQS Error: {operation} with {algorithm} expected {expected} bytes, got {actual}"
+            ),
+            Error::Failed {
+                operation,
+                algorithm,
+            } => write!(f, "OQS Error: {operation} with {algorithm} failed"),
+            Error::VerificationFailed { algorithm } => {
+                write!(f, "OQS Error: {algorithm} signature is not valid")
+            }
         }
     }
 }
 
+impl Error {
+    /// Attach the operation and algorithm to a generic error from liboqs
+    fn in_operation(self, operation: Operation, algorithm: &'static str) -> Self {
+        match (self, operation) {
+            (Error::Error, Operation::Verify) => Error::VerificationFailed { algorithm },
+            (Error::Error, operation) => Error::Failed {
+                operation,
+                algorithm,
+            },
+            (error, _) => error,
+        }
+    }
+}
+
+/// Fails with [`Error::WrongLength`] unless `actual` is `expected`
+pub(crate) fn check_length(
+    operation: Operation,
+    algorithm: &'static str,
+    expected: usize,
+    actual: usize,
+) -> Result<()> {
+    if actual != expected {
+        return Err(Error::WrongLength {
+            operation,
+            algorithm,
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Operations, as recorded in [`Error`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+    /// Key generation
+    Keypair,
+    /// Encapsulation
+    Encapsulate,
+    /// Decapsulation
+    Decapsulate,
+    /// Signing
+    Sign,
+    /// Verification of a signature
+    Verify,
+    /// Encoding of a key as DER or PEM
+    Encode,
+    /// Decoding of a key, ciphertext or signature from bytes, DER or PEM
+    Decode,
+    /// Reading how many signatures a stateful secret key can still make
+    SigsRemaining,
+    /// Reading how many signatures a stateful secret key can make in total
+    SigsTotal,
+    /// Encryption, or generation of a keystream
+    Encrypt,
+    /// Decryption
+    Decrypt,
+    /// Derivation of the keys of an HPKE context
+    KeySchedule,
+}
+
+impl core::fmt::Display for Operation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Operation::Keypair => "key generation",
+            Operation::Encapsulate => "encapsulation",
+            Operation::Decapsulate => "decapsulation",
+            Operation::Sign => "signing",
+            Operation::Verify => "verification",
+            Operation::Encode => "encoding",
+            Operation::Decode => "decoding",
+            Operation::SigsRemaining => "counting the remaining signatures",
+            Operation::SigsTotal => "counting the total signatures",
+            Operation::Encrypt => "encryption",
+            Operation::Decrypt => "decryption",
+            Operation::KeySchedule => "key schedule",
+        })
+    }
+}
+
 /// Convert an OQS_STATUS to the Result type.
 fn status_to_result(status: OQS_STATUS) -> Result<()> {
     match status {
@@ -115,5 +340,36 @@ fn status_to_result(status: OQS_STATUS) -> Result<()> {
     }
 }
 
+/// Standardization status of an algorithm
+///
+/// Optional support for `serde` if that feature is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Standardization {
+    /// Published as a standard, such as ML-KEM in FIPS 203 and ML-DSA in FIPS 204
+    Standardized,
+    /// Selected for standardization, with the standard still being drafted
+    Draft,
+    /// Candidate in the ongoing NIST process for additional signatures
+    Candidate,
+    /// Not selected for standardization by NIST
+    Experimental,
+    /// Superseded by a standard, such as Kyber by ML-KEM
+    Deprecated,
+}
+
+#[cfg(oqs_aes)]
+pub mod aes;
+#[cfg(feature = "composite")]
+pub mod composite;
+pub mod cpu;
+#[cfg(oqs_hash)]
+pub mod hash;
+#[cfg(feature = "hpke")]
+pub mod hpke;
+#[cfg(feature = "hybrid")]
+pub mod hybrid;
 pub mod kem;
+pub mod rand;
 pub mod sig;
+pub mod sig_stfl;
@@ -1,6 +1,12 @@
 //! Defines macros to implement buffers for public/private keys
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 /// Implements a buffer for cryptographic objects
+///
+/// Buffers declared as `secret` are wiped from memory when they are dropped,
+/// only show their length when formatted with `Debug`,
+/// and are compared in constant time.
 #[macro_export]
 macro_rules! newtype_buffer {
     ($name: ident, $name_ref: ident) => {
@@ -31,6 +37,103 @@ macro_rules! newtype_buffer {
             bytes: &'a [u8],
         }
 
+        $crate::newtype_buffer!(@common $name, $name_ref);
+    };
+    (secret $name: ident, $name_ref: ident) => {
+        /// New owned buffer holding secret material
+        ///
+        /// Construct the reference version of this type through the algorithm API functions.
+        ///
+        /// The contents are wiped from memory when the buffer is dropped.
+        /// Equality is checked in constant time, see [`ConstantTimeEq`](crate::ConstantTimeEq),
+        /// and the `Debug` output does not include the contents.
+        ///
+        /// Optional support for `serde` if that feature is enabled.
+        #[derive(Clone)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub struct $name {
+            #[cfg_attr(
+                feature = "serde",
+                serde(deserialize_with = "crate::macros::deserialize_secret_bytes")
+            )]
+            bytes: Vec<u8>,
+        }
+
+        impl $name {
+            /// Obtain the contained vector
+            ///
+            /// The returned vector is no longer wiped on drop;
+            /// that becomes the responsibility of the caller.
+            pub fn into_vec(mut self) -> Vec<u8> {
+                core::mem::take(&mut self.bytes)
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                $crate::macros::cleanse(&mut self.bytes);
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("len", &self.bytes.len())
+                    .finish_non_exhaustive()
+            }
+        }
+
+        impl $crate::ConstantTimeEq for $name {
+            fn ct_eq(&self, other: &Self) -> $crate::subtle::Choice {
+                $crate::ConstantTimeEq::ct_eq(self.bytes.as_slice(), other.bytes.as_slice())
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                $crate::ConstantTimeEq::ct_eq(self, other).into()
+            }
+        }
+
+        impl Eq for $name {}
+
+        /// Reference version of this type.
+        ///
+        /// Allows for copy-less usage
+        /// Construct it through the algorithm API functions
+        ///
+        /// Like the owned version, this is compared in constant time
+        /// and does not show its contents when formatted with `Debug`.
+        #[derive(Clone, Copy)]
+        pub struct $name_ref<'a> {
+            bytes: &'a [u8],
+        }
+
+        impl<'a> core::fmt::Debug for $name_ref<'a> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name_ref))
+                    .field("len", &self.bytes.len())
+                    .finish_non_exhaustive()
+            }
+        }
+
+        impl<'a> $crate::ConstantTimeEq for $name_ref<'a> {
+            fn ct_eq(&self, other: &Self) -> $crate::subtle::Choice {
+                $crate::ConstantTimeEq::ct_eq(self.bytes, other.bytes)
+            }
+        }
+
+        impl<'a> PartialEq for $name_ref<'a> {
+            fn eq(&self, other: &Self) -> bool {
+                $crate::ConstantTimeEq::ct_eq(self, other).into()
+            }
+        }
+
+        impl<'a> Eq for $name_ref<'a> {}
+
+        $crate::newtype_buffer!(@common $name, $name_ref);
+    };
+    (@common $name: ident, $name_ref: ident) => {
         impl<'a> $name_ref<'a> {
             /// Construct a new container around this reference version
             fn new(bytes: &'a [u8]) -> $name_ref<'a> {
@@ -80,6 +183,267 @@ macro_rules! newtype_buffer {
     };
 }
 
+/// Implements a fixed-size buffer for the statically-typed API
+///
+/// The buffer is generic over an algorithm trait, whose associated type
+/// `$bytes` is the `[u8; N]` array that holds the contents.
+/// Arrays are kept on the heap, as some keys are too large for the stack.
+///
+/// Buffers declared as `secret` are wiped, redacted and compared like
+/// those of `newtype_buffer!`.
+#[macro_export]
+macro_rules! newtype_array {
+    ($name: ident, $trait: ident :: $bytes: ident) => {
+        /// New owned fixed-size buffer
+        ///
+        /// The length is determined by the algorithm `A`.
+        pub struct $name<A: $trait> {
+            bytes: alloc::boxed::Box<A::$bytes>,
+        }
+
+        impl<A: $trait> core::fmt::Debug for $name<A> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("algorithm", &A::ALGORITHM)
+                    .field("bytes", &self.as_ref())
+                    .finish()
+            }
+        }
+
+        impl<A: $trait> PartialEq for $name<A> {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_ref() == other.as_ref()
+            }
+        }
+
+        impl<A: $trait> Eq for $name<A> {}
+
+        $crate::newtype_array!(@common $name, $trait::$bytes);
+    };
+    (secret $name: ident, $trait: ident :: $bytes: ident) => {
+        /// New owned fixed-size buffer holding secret material
+        ///
+        /// The length is determined by the algorithm `A`.
+        ///
+        /// The contents are wiped from memory when the buffer is dropped.
+        /// Equality is checked in constant time, see [`ConstantTimeEq`](crate::ConstantTimeEq),
+        /// and the `Debug` output does not include the contents.
+        pub struct $name<A: $trait> {
+            bytes: alloc::boxed::Box<A::$bytes>,
+        }
+
+        impl<A: $trait> Drop for $name<A> {
+            fn drop(&mut self) {
+                let bytes = $crate::ByteArray::as_mut_slice(&mut *self.bytes);
+                // SAFETY: the pointer is valid for writes of `bytes.len()` bytes
+                unsafe {
+                    $crate::ffi::common::OQS_MEM_cleanse(
+                        bytes.as_mut_ptr() as *mut libc::c_void,
+                        bytes.len(),
+                    )
+                };
+            }
+        }
+
+        impl<A: $trait> core::fmt::Debug for $name<A> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("algorithm", &A::ALGORITHM)
+                    .finish_non_exhaustive()
+            }
+        }
+
+        impl<A: $trait> $crate::ConstantTimeEq for $name<A> {
+            fn ct_eq(&self, other: &Self) -> $crate::subtle::Choice {
+                $crate::ConstantTimeEq::ct_eq(self.as_ref(), other.as_ref())
+            }
+        }
+
+        impl<A: $trait> PartialEq for $name<A> {
+            fn eq(&self, other: &Self) -> bool {
+                $crate::ConstantTimeEq::ct_eq(self, other).into()
+            }
+        }
+
+        impl<A: $trait> Eq for $name<A> {}
+
+        $crate::newtype_array!(@common $name, $trait::$bytes);
+    };
+    (@common $name: ident, $trait: ident :: $bytes: ident) => {
+        impl<A: $trait> $name<A> {
+            /// Length in bytes
+            pub const LENGTH: usize = <A::$bytes as $crate::ByteArray>::LENGTH;
+
+            /// Construct a zeroed buffer, to be filled by liboqs
+            fn zeroed() -> Self {
+                $name {
+                    bytes: <A::$bytes as $crate::ByteArray>::boxed_zeroed(),
+                }
+            }
+
+            /// Construct this buffer from bytes
+            ///
+            /// Fails with [`Error::WrongLength`](crate::Error::WrongLength)
+            /// unless `bytes` holds exactly [`Self::LENGTH`] bytes.
+            pub fn from_bytes(bytes: &[u8]) -> $crate::Result<Self> {
+                $crate::check_length(
+                    $crate::Operation::Decode,
+                    A::ALGORITHM.name(),
+                    Self::LENGTH,
+                    bytes.len(),
+                )?;
+                let mut buf = Self::zeroed();
+                $crate::ByteArray::as_mut_slice(&mut *buf.bytes).copy_from_slice(bytes);
+                Ok(buf)
+            }
+
+            /// Get the contained array
+            pub fn as_array(&self) -> &A::$bytes {
+                &self.bytes
+            }
+        }
+
+        impl<A: $trait> Clone for $name<A> {
+            fn clone(&self) -> Self {
+                // Cloning the box itself would copy the array through the stack
+                let mut buf = Self::zeroed();
+                $crate::ByteArray::as_mut_slice(&mut *buf.bytes).copy_from_slice(self.as_ref());
+                buf
+            }
+        }
+
+        impl<A: $trait> AsRef<[u8]> for $name<A> {
+            fn as_ref(&self) -> &[u8] {
+                $crate::ByteArray::as_slice(&*self.bytes)
+            }
+        }
+
+        impl<'a, A: $trait> TryFrom<&'a [u8]> for $name<A> {
+            type Error = $crate::Error;
+
+            fn try_from(bytes: &'a [u8]) -> $crate::Result<Self> {
+                Self::from_bytes(bytes)
+            }
+        }
+    };
+}
+
+/// Fixed-size byte arrays that hold the buffers of the statically-typed API
+///
+/// This is implemented for all `[u8; N]`, and cannot be implemented for other types.
+pub trait ByteArray: Send + Sync + 'static + sealed::Sealed {
+    /// Length of the array
+    const LENGTH: usize;
+
+    /// Allocate a zeroed array on the heap, without going through the stack
+    fn boxed_zeroed() -> Box<Self>;
+
+    /// View the array as a slice
+    fn as_slice(&self) -> &[u8];
+
+    /// View the array as a mutable slice
+    fn as_mut_slice(&mut self) -> &mut [u8];
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl<const N: usize> Sealed for [u8; N] {}
+}
+
+impl<const N: usize> ByteArray for [u8; N] {
+    const LENGTH: usize = N;
+
+    fn boxed_zeroed() -> Box<Self> {
+        alloc::vec![0u8; N]
+            .into_boxed_slice()
+            .try_into()
+            .expect("the vector has length N")
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+/// Overwrite the whole allocation of `bytes` with zeroes
+///
+/// This also covers the spare capacity, which may hold secrets
+/// that were written by liboqs before the length was set.
+pub(crate) fn cleanse(bytes: &mut Vec<u8>) {
+    if bytes.capacity() > 0 {
+        // SAFETY: the pointer is valid for writes of `capacity` bytes
+        unsafe {
+            crate::ffi::common::OQS_MEM_cleanse(
+                bytes.as_mut_ptr() as *mut libc::c_void,
+                bytes.capacity(),
+            )
+        };
+    }
+}
+
+/// Deserialize the bytes of a secret buffer
+///
+/// Growing a `Vec` while reading a sequence leaves copies of the
+/// contents behind in freed memory, so we grow it by hand and wipe
+/// every buffer we leave behind.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_secret_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::{SeqAccess, Visitor};
+
+    /// Wipes the partially read secret if deserialization fails
+    struct Guard(Vec<u8>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            cleanse(&mut self.0);
+        }
+    }
+
+    struct SecretBytesVisitor;
+
+    impl<'de> Visitor<'de> for SecretBytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.write_str("a sequence of bytes")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            // don't trust the size hint for more than a few pages
+            let capacity = seq.size_hint().unwrap_or(0).min(16 * 1024);
+            let mut buf = Guard(Vec::with_capacity(capacity));
+            while let Some(byte) = seq.next_element::<u8>()? {
+                if buf.0.len() == buf.0.capacity() {
+                    let mut grown = Vec::with_capacity((buf.0.capacity() * 2).max(64));
+                    grown.extend_from_slice(&buf.0);
+                    // the old buffer is wiped when the guard drops it
+                    drop(core::mem::replace(&mut buf, Guard(grown)));
+                }
+                buf.0.push(byte);
+            }
+            Ok(core::mem::take(&mut buf.0))
+        }
+
+        fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+    }
+
+    deserializer.deserialize_seq(SecretBytesVisitor)
+}
+
 #[cfg(test)]
 mod test {
     use alloc::vec;
@@ -90,6 +454,7 @@ mod test {
     use serde::{Deserialize, Serialize};
 
     newtype_buffer!(TestBuf, TestBufRef);
+    newtype_buffer!(secret TestSecretBuf, TestSecretBufRef);
 
     #[test]
     fn test_get_reference() {
@@ -124,4 +489,69 @@ mod test {
         };
         assert_eq!(refbuf.to_owned(), buf)
     }
+
+    #[test]
+    fn test_secret_into_vec() {
+        let buf = TestSecretBuf {
+            bytes: vec![1, 2, 3],
+        };
+        assert_eq!(buf.into_vec(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_secret_to_owned() {
+        let bytes = vec![1, 2, 3];
+        let refbuf = TestSecretBufRef::new(bytes.as_ref());
+        let buf = TestSecretBuf {
+            bytes: bytes.clone(),
+        };
+        assert_eq!(refbuf.to_owned(), buf);
+        assert_eq!(buf.clone(), buf);
+    }
+
+    #[test]
+    fn test_secret_len() {
+        let buf = TestSecretBuf {
+            bytes: vec![1, 2, 3],
+        };
+        assert_eq!(buf.len(), 3);
+    }
+
+    #[test]
+    fn test_secret_debug_is_redacted() {
+        let buf = TestSecretBuf {
+            bytes: vec![0xde, 0xad, 0xbe, 0xef],
+        };
+        assert_eq!(alloc::format!("{:?}", buf), "TestSecretBuf { len: 4, .. }");
+        let refbuf = TestSecretBufRef::from(&buf);
+        assert_eq!(
+            alloc::format!("{:?}", refbuf),
+            "TestSecretBufRef { len: 4, .. }"
+        );
+    }
+
+    #[test]
+    fn test_secret_ct_eq() {
+        use crate::ConstantTimeEq;
+        let a = TestSecretBuf {
+            bytes: vec![1, 2, 3],
+        };
+        let b = TestSecretBuf {
+            bytes: vec![1, 2, 4],
+        };
+        let c = TestSecretBuf { bytes: vec![1, 2] };
+        assert!(bool::from(a.ct_eq(&a.clone())));
+        assert!(!bool::from(a.ct_eq(&b)));
+        assert!(!bool::from(a.ct_eq(&c)));
+        assert_ne!(a, b);
+        assert_eq!(TestSecretBufRef::from(&a), TestSecretBufRef::from(&a));
+    }
+
+    #[test]
+    fn test_cleanse() {
+        let mut bytes = Vec::with_capacity(8);
+        bytes.extend_from_slice(&[1, 2, 3]);
+        super::cleanse(&mut bytes);
+        assert_eq!(bytes, vec![0, 0, 0]);
+    }
 }
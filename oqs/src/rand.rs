@@ -0,0 +1,652 @@
+//! Random number generation
+//!
+//! liboqs draws all randomness for key generation, encapsulation and signing
+//! from a single, process-wide source. By default this is the operating
+//! system's generator, or OpenSSL's if liboqs was built with OpenSSL.
+//!
+//! Use [`switch_algorithm`] to pick one of the built-in sources, or, with the
+//! `rand_core` feature, [`set_rng`] to have liboqs use any
+//! [`CryptoRngCore`](rand_core::CryptoRngCore).
+//! This is mostly useful on `no_std` targets, where liboqs may not have
+//! access to a system generator.
+//!
+//! With the `deterministic_rng` feature, [`with_deterministic_rng`] temporarily
+//! replaces the generator by the NIST AES-256 CTR DRBG that is used to
+//! produce the known-answer-test files of the NIST submissions.
+use crate::ffi::rand as ffi;
+use crate::*;
+
+#[cfg(all(test, feature = "hybrid"))]
+pub(crate) use source::with_fixed_bytes;
+#[cfg(any(feature = "rand_core", feature = "deterministic_rng"))]
+use source::*;
+
+/// Built-in random number generators of liboqs
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Algorithm {
+    /// The operating system's random number generator
+    System,
+    /// OpenSSL's random number generator
+    ///
+    /// Only available if liboqs was built with OpenSSL.
+    OpenSsl,
+}
+
+impl Algorithm {
+    fn to_id(self) -> *const libc::c_char {
+        let id: &[u8] = match self {
+            Algorithm::System => &ffi::OQS_RAND_alg_system[..],
+            Algorithm::OpenSsl => &ffi::OQS_RAND_alg_openssl[..],
+        };
+        id as *const _ as *const libc::c_char
+    }
+}
+
+/// Custom generators that liboqs calls back into
+#[cfg(any(feature = "rand_core", feature = "deterministic_rng"))]
+mod source {
+    use alloc::boxed::Box;
+
+    use super::{ffi, Algorithm};
+    use crate::lock::Lock;
+    #[cfg(all(test, feature = "hybrid"))]
+    use crate::{Error, Result};
+
+    /// Source of randomness that we hand to liboqs
+    pub(crate) trait RandomSource: Send {
+        fn fill_bytes(&mut self, buf: &mut [u8]);
+    }
+
+    /// The source liboqs calls into through [`custom_randombytes`]
+    ///
+    /// `None` if liboqs uses one of its built-in generators.
+    static SOURCE: Lock<Option<Box<dyn RandomSource>>> = Lock::new(None);
+
+    /// The built-in generator that was last selected
+    ///
+    /// `None` if liboqs still uses its default.
+    static BUILTIN: Lock<Option<Algorithm>> = Lock::new(None);
+
+    unsafe extern "C" fn custom_randombytes(random_array: *mut u8, bytes_to_read: usize) {
+        if bytes_to_read == 0 {
+            return;
+        }
+        let buf = core::slice::from_raw_parts_mut(random_array, bytes_to_read);
+        let filled = SOURCE.with(|source| match source {
+            Some(source) => {
+                fill_or_abort(source.as_mut(), buf);
+                true
+            }
+            None => false,
+        });
+        if !filled {
+            // liboqs switched to a built-in generator while calling into us
+            ffi::OQS_randombytes_system(random_array, bytes_to_read);
+        }
+    }
+
+    /// Fill `buf` from `source`, aborting if it panics
+    ///
+    /// A panic must not unwind into liboqs.
+    fn fill_or_abort(source: &mut dyn RandomSource, buf: &mut [u8]) {
+        #[cfg(feature = "std")]
+        {
+            let fill = std::panic::AssertUnwindSafe(|| source.fill_bytes(buf));
+            if std::panic::catch_unwind(fill).is_err() {
+                abort("the random number generator panicked");
+            }
+        }
+        // panics can't unwind out of an `extern "C"` function, so this aborts too
+        #[cfg(not(feature = "std"))]
+        source.fill_bytes(buf)
+    }
+
+    /// Abort the process because of a failure inside a call from liboqs
+    #[cfg(any(feature = "std", all(test, feature = "hybrid")))]
+    fn abort(message: &str) -> ! {
+        std::eprintln!("oqs: {message}, aborting");
+        std::process::abort()
+    }
+
+    /// Run `f` while no other thread changes the generator liboqs uses
+    ///
+    /// Panics if `f` tries to change the generator itself.
+    #[cfg(feature = "std")]
+    pub(crate) fn exclusive<T>(f: impl FnOnce() -> T) -> T {
+        use core::cell::Cell;
+
+        static SCOPE: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        std::thread_local! {
+            static HELD: Cell<bool> = const { Cell::new(false) };
+        }
+
+        /// Marks the lock as released by this thread when dropped
+        struct Release;
+
+        impl Drop for Release {
+            fn drop(&mut self) {
+                HELD.with(|held| held.set(false));
+            }
+        }
+
+        // waiting for ourselves would deadlock
+        assert!(
+            !HELD.with(|held| held.get()),
+            "the random number generator can't be changed inside with_deterministic_rng"
+        );
+        let _scope = SCOPE.lock().unwrap_or_else(|e| e.into_inner());
+        HELD.with(|held| held.set(true));
+        let _release = Release;
+        f()
+    }
+
+    /// Without `std` there is no lock, callers have to coordinate themselves
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn exclusive<T>(f: impl FnOnce() -> T) -> T {
+        f()
+    }
+
+    /// Make liboqs draw randomness from `source`
+    ///
+    /// Returns the previously installed source, if any.
+    pub(crate) fn replace_source(source: Box<dyn RandomSource>) -> Option<Box<dyn RandomSource>> {
+        let previous = SOURCE.with(|slot| slot.replace(source));
+        unsafe { ffi::OQS_randombytes_custom_algorithm(Some(custom_randombytes)) };
+        previous
+    }
+
+    /// Record that liboqs now uses a built-in generator
+    pub(crate) fn switched_to_builtin(algorithm: Option<Algorithm>) {
+        // liboqs no longer calls into our source, so it can go
+        SOURCE.with(|slot| slot.take());
+        if algorithm.is_some() {
+            BUILTIN.with(|builtin| *builtin = algorithm);
+        }
+    }
+
+    /// Switch back to the built-in generator that was used last
+    ///
+    /// Called while unwinding, so this must not panic. If liboqs refuses
+    /// to switch, it keeps calling [`custom_randombytes`], which falls back
+    /// to the system generator once the source is removed.
+    #[cfg(any(feature = "deterministic_rng", all(test, feature = "hybrid")))]
+    fn restore_builtin() {
+        let builtin = BUILTIN.with(|builtin| *builtin);
+        let switch = |alg: Algorithm| {
+            let status = unsafe { ffi::OQS_randombytes_switch_algorithm(alg.to_id()) };
+            status == ffi::OQS_STATUS::OQS_SUCCESS
+        };
+        match builtin {
+            Some(alg) => {
+                switch(alg);
+            }
+            // liboqs defaults to OpenSSL if it was built with it
+            None => {
+                let _ = switch(Algorithm::OpenSsl) || switch(Algorithm::System);
+            }
+        }
+        switched_to_builtin(None);
+    }
+
+    /// Hands the draws of one thread to `source`, and those of all other
+    /// threads to the system generator
+    #[cfg(all(
+        feature = "std",
+        any(feature = "deterministic_rng", all(test, feature = "hybrid"))
+    ))]
+    struct Scoped {
+        owner: std::thread::ThreadId,
+        source: Box<dyn RandomSource>,
+    }
+
+    #[cfg(all(
+        feature = "std",
+        any(feature = "deterministic_rng", all(test, feature = "hybrid"))
+    ))]
+    impl RandomSource for Scoped {
+        fn fill_bytes(&mut self, buf: &mut [u8]) {
+            if std::thread::current().id() == self.owner {
+                self.source.fill_bytes(buf)
+            } else {
+                unsafe { ffi::OQS_randombytes_system(buf.as_mut_ptr(), buf.len()) }
+            }
+        }
+    }
+
+    /// Run `f` with liboqs drawing all randomness from `source`
+    ///
+    /// With the `std` feature, only the calling thread draws from `source`,
+    /// other threads get the system generator in the meantime.
+    /// Concurrent calls and changes of the generator wait for each other,
+    /// and calls must not be nested.
+    /// Afterwards, the previous generator is restored, also when `f` panics.
+    #[cfg(any(feature = "deterministic_rng", all(test, feature = "hybrid")))]
+    pub(crate) fn with_source<T>(source: Box<dyn RandomSource>, f: impl FnOnce() -> T) -> T {
+        /// Puts the previous generator back when dropped
+        struct Restore(Option<Box<dyn RandomSource>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                match self.0.take() {
+                    Some(previous) => {
+                        replace_source(previous);
+                    }
+                    None => restore_builtin(),
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
+        let source = Box::new(Scoped {
+            owner: std::thread::current().id(),
+            source,
+        });
+        exclusive(|| {
+            let _restore = Restore(replace_source(source));
+            f()
+        })
+    }
+
+    /// Run `f` with liboqs drawing exactly the given `bytes` as randomness
+    ///
+    /// This makes algorithms that liboqs does not offer a deterministic
+    /// variant of reproducible, if it is known how much randomness they use.
+    /// Returns [`Error::Error`] if `f` used fewer than `bytes`,
+    /// and aborts the process if it asks for more.
+    #[cfg(all(test, feature = "hybrid"))]
+    pub(crate) fn with_fixed_bytes<T>(bytes: &[u8], f: impl FnOnce() -> T) -> Result<T> {
+        use alloc::sync::Arc;
+        use alloc::vec::Vec;
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        struct FixedBytes {
+            bytes: Vec<u8>,
+            used: usize,
+            mismatch: Arc<AtomicBool>,
+        }
+
+        impl RandomSource for FixedBytes {
+            fn fill_bytes(&mut self, buf: &mut [u8]) {
+                match self.bytes.get(self.used..self.used + buf.len()) {
+                    Some(bytes) => {
+                        buf.copy_from_slice(bytes);
+                        self.used += buf.len();
+                    }
+                    // can't panic across the FFI boundary, and must not hand out
+                    // anything that isn't random
+                    None => abort("liboqs asked for more than the fixed random bytes"),
+                }
+            }
+        }
+
+        impl Drop for FixedBytes {
+            fn drop(&mut self) {
+                // all bytes must have been drawn
+                if self.used != self.bytes.len() {
+                    self.mismatch.store(true, Ordering::Relaxed);
+                }
+                crate::macros::cleanse(&mut self.bytes);
+            }
+        }
+
+        let mismatch = Arc::new(AtomicBool::new(false));
+        let source = FixedBytes {
+            bytes: bytes.to_vec(),
+            used: 0,
+            mismatch: mismatch.clone(),
+        };
+        let result = with_source(Box::new(source), f);
+        if mismatch.load(Ordering::Relaxed) {
+            return Err(Error::Error);
+        }
+        Ok(result)
+    }
+}
+
+/// Switch liboqs to one of its built-in random number generators
+///
+/// This also removes any generator installed through [`set_rng`].
+///
+/// Returns [`Error::AlgorithmDisabled`] if the generator is not available
+/// in the linked liboqs, for example OpenSSL in builds without OpenSSL.
+/// In that case, the current generator stays in use.
+///
+/// With the `std` feature, this waits for a running `with_deterministic_rng`
+/// to finish, and panics if called inside one.
+pub fn switch_algorithm(algorithm: Algorithm) -> Result<()> {
+    let switch = || {
+        let status = unsafe { ffi::OQS_randombytes_switch_algorithm(algorithm.to_id()) };
+        match status_to_result(status) {
+            Ok(()) => {
+                #[cfg(any(feature = "rand_core", feature = "deterministic_rng"))]
+                switched_to_builtin(Some(algorithm));
+                Ok(())
+            }
+            Err(Error::Error) => Err(Error::AlgorithmDisabled),
+            Err(e) => Err(e),
+        }
+    };
+    #[cfg(any(feature = "rand_core", feature = "deterministic_rng"))]
+    return exclusive(switch);
+    #[cfg(not(any(feature = "rand_core", feature = "deterministic_rng")))]
+    switch()
+}
+
+/// Make liboqs use `rng` for all randomness
+///
+/// The generator is shared by all threads and is locked while
+/// liboqs draws from it.
+/// It stays installed until it is replaced by another call to this function
+/// or by [`switch_algorithm`].
+///
+/// With the `std` feature, this waits for a running `with_deterministic_rng`
+/// to finish, and panics if called inside one.
+///
+/// Panicking inside `rng` aborts the process, as the panic can't
+/// unwind through liboqs.
+#[cfg(feature = "rand_core")]
+pub fn set_rng<R>(rng: R)
+where
+    R: rand_core::CryptoRngCore + Send + 'static,
+{
+    struct RngSource<R>(R);
+
+    impl<R: rand_core::CryptoRngCore + Send> RandomSource for RngSource<R> {
+        fn fill_bytes(&mut self, buf: &mut [u8]) {
+            self.0.fill_bytes(buf)
+        }
+    }
+
+    exclusive(|| replace_source(alloc::boxed::Box::new(RngSource(rng))));
+}
+
+/// Fill `buf` with random bytes from the generator liboqs currently uses
+pub fn randombytes(buf: &mut [u8]) {
+    // On some systems, getentropy fails if given a zero-length array
+    if !buf.is_empty() {
+        unsafe { ffi::OQS_randombytes(buf.as_mut_ptr(), buf.len()) };
+    }
+}
+
+/// The NIST AES-256 CTR DRBG used for the known-answer tests
+///
+/// This is the generator from `rng.c` that came with the NIST submission
+/// packages. Its output is fully determined by the seed,
+/// so it must never be used to create keys that protect anything.
+///
+/// See [`with_deterministic_rng`] to make liboqs use it.
+#[cfg(feature = "deterministic_rng")]
+pub struct NistKatRng {
+    key: [u8; 32],
+    v: [u8; 16],
+}
+
+#[cfg(feature = "deterministic_rng")]
+impl NistKatRng {
+    /// Seed the generator
+    ///
+    /// This is `randombytes_init(seed, NULL, 256)` in the NIST code.
+    pub fn new(seed: &[u8; 48]) -> Self {
+        let mut rng = NistKatRng {
+            key: [0; 32],
+            v: [0; 16],
+        };
+        rng.update(Some(seed));
+        rng
+    }
+
+    fn increment_v(&mut self) {
+        for byte in self.v.iter_mut().rev() {
+            let (next, overflow) = byte.overflowing_add(1);
+            *byte = next;
+            if !overflow {
+                break;
+            }
+        }
+    }
+
+    fn next_block(&mut self, cipher: &::aes::Aes256) -> [u8; 16] {
+        use ::aes::cipher::{generic_array::GenericArray, BlockEncrypt};
+        self.increment_v();
+        let mut block = GenericArray::from(self.v);
+        cipher.encrypt_block(&mut block);
+        block.into()
+    }
+
+    fn update(&mut self, provided_data: Option<&[u8; 48]>) {
+        use ::aes::cipher::{generic_array::GenericArray, KeyInit};
+        let cipher = ::aes::Aes256::new(GenericArray::from_slice(&self.key));
+        let mut temp = [0u8; 48];
+        for chunk in temp.chunks_exact_mut(16) {
+            chunk.copy_from_slice(&self.next_block(&cipher));
+        }
+        if let Some(data) = provided_data {
+            temp.iter_mut().zip(data).for_each(|(t, d)| *t ^= d);
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
+    }
+
+    /// Fill `buf` with the next output of the generator
+    ///
+    /// This is `randombytes(buf, buf.len())` in the NIST code.
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        use ::aes::cipher::{generic_array::GenericArray, KeyInit};
+        let cipher = ::aes::Aes256::new(GenericArray::from_slice(&self.key));
+        for chunk in buf.chunks_mut(16) {
+            let block = self.next_block(&cipher);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        self.update(None);
+    }
+}
+
+#[cfg(feature = "deterministic_rng")]
+impl Drop for NistKatRng {
+    fn drop(&mut self) {
+        unsafe {
+            crate::ffi::common::OQS_MEM_cleanse(self.key.as_mut_ptr() as *mut libc::c_void, 32);
+            crate::ffi::common::OQS_MEM_cleanse(self.v.as_mut_ptr() as *mut libc::c_void, 16);
+        }
+    }
+}
+
+#[cfg(feature = "deterministic_rng")]
+impl RandomSource for NistKatRng {
+    fn fill_bytes(&mut self, buf: &mut [u8]) {
+        NistKatRng::fill_bytes(self, buf)
+    }
+}
+
+/// Run `f` with liboqs drawing all randomness from a [`NistKatRng`] seeded with `seed`
+///
+/// Key generation, encapsulation and signing inside `f` are reproducible,
+/// and match the NIST known-answer tests when given the seeds listed there.
+///
+/// Only the calling thread draws from the generator. With the `std` feature,
+/// other threads that use liboqs while `f` runs get the system generator,
+/// and concurrent calls to this function, [`switch_algorithm`] and `set_rng`
+/// wait for each other. Calling any of them inside `f` panics.
+/// Without `std`, the generator is shared by all threads.
+///
+/// Afterwards, the previous generator is restored, also when `f` panics.
+#[cfg(feature = "deterministic_rng")]
+pub fn with_deterministic_rng<T>(seed: &[u8; 48], f: impl FnOnce() -> T) -> T {
+    with_source(alloc::boxed::Box::new(NistKatRng::new(seed)), f)
+}
+
+/// Serialize the tests that change or depend on the generator liboqs uses
+///
+/// The test harness runs tests in parallel, but the generator is process-wide.
+#[cfg(test)]
+pub(crate) fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_randombytes() {
+        let _lock = test_lock();
+        crate::init();
+        let mut buf = [0u8; 32];
+        randombytes(&mut buf);
+        assert_ne!(buf, [0u8; 32]);
+        randombytes(&mut []);
+    }
+
+    #[test]
+    fn test_switch_system() -> Result<()> {
+        let _lock = test_lock();
+        crate::init();
+        switch_algorithm(Algorithm::System)
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn test_set_rng() -> Result<()> {
+        /// Not random at all, but lets us recognize the output
+        struct CountingRng(u8);
+
+        impl rand_core::RngCore for CountingRng {
+            fn next_u32(&mut self) -> u32 {
+                rand_core::impls::next_u32_via_fill(self)
+            }
+            fn next_u64(&mut self) -> u64 {
+                rand_core::impls::next_u64_via_fill(self)
+            }
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                for byte in dest {
+                    *byte = self.0;
+                    self.0 = self.0.wrapping_add(1);
+                }
+            }
+            fn try_fill_bytes(
+                &mut self,
+                dest: &mut [u8],
+            ) -> core::result::Result<(), rand_core::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+        impl rand_core::CryptoRng for CountingRng {}
+
+        let _lock = test_lock();
+        crate::init();
+        set_rng(CountingRng(1));
+        let mut buf = [0u8; 4];
+        randombytes(&mut buf);
+        switch_algorithm(Algorithm::System)?;
+        // other tests may have drawn from the generator in the meantime
+        assert!(buf
+            .windows(2)
+            .all(|pair| pair[1] == pair[0].wrapping_add(1)));
+        Ok(())
+    }
+
+    #[cfg(feature = "deterministic_rng")]
+    #[test]
+    fn test_nist_kat_rng() {
+        let mut entropy_input = [0u8; 48];
+        for (i, byte) in entropy_input.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut rng = NistKatRng::new(&entropy_input);
+        // The seed of `count = 0` in every NIST KAT file
+        let mut seed = [0u8; 48];
+        rng.fill_bytes(&mut seed);
+        assert_eq!(
+            seed[..],
+            [
+                0x06, 0x15, 0x50, 0x23, 0x4D, 0x15, 0x8C, 0x5E, 0xC9, 0x55, 0x95, 0xFE, 0x04, 0xEF,
+                0x7A, 0x25, 0x76, 0x7F, 0x2E, 0x24, 0xCC, 0x2B, 0xC4, 0x79, 0xD0, 0x9D, 0x86, 0xDC,
+                0x9A, 0xBC, 0xFD, 0xE7, 0x05, 0x6A, 0x8C, 0x26, 0x6F, 0x9E, 0xF9, 0x7E, 0xD0, 0x85,
+                0x41, 0xDB, 0xD2, 0xE1, 0xFF, 0xA1,
+            ][..]
+        );
+    }
+
+    #[cfg(feature = "deterministic_rng")]
+    #[test]
+    fn test_with_deterministic_rng() {
+        let _lock = test_lock();
+        crate::init();
+        let seed = [7u8; 48];
+        let draw = || {
+            let mut buf = [0u8; 64];
+            randombytes(&mut buf);
+            buf
+        };
+        let first = with_deterministic_rng(&seed, draw);
+        let second = with_deterministic_rng(&seed, draw);
+        assert_eq!(first, second);
+
+        let mut expected = [0u8; 64];
+        NistKatRng::new(&seed).fill_bytes(&mut expected);
+        assert_eq!(first, expected);
+    }
+
+    #[cfg(all(feature = "deterministic_rng", feature = "std"))]
+    #[test]
+    fn test_with_deterministic_rng_restores_on_panic() {
+        let _lock = test_lock();
+        crate::init();
+        let seed = [9u8; 48];
+        let result = std::panic::catch_unwind(|| {
+            with_deterministic_rng(&seed, || panic!("expected panic"));
+        });
+        assert!(result.is_err());
+
+        let mut buf = [0u8; 64];
+        randombytes(&mut buf);
+        let mut expected = [0u8; 64];
+        NistKatRng::new(&seed).fill_bytes(&mut expected);
+        assert_ne!(buf, expected);
+    }
+
+    #[cfg(all(feature = "deterministic_rng", feature = "ml_kem"))]
+    #[test]
+    fn test_deterministic_kem() -> Result<()> {
+        use crate::kem::{Algorithm, Kem};
+        let _lock = test_lock();
+        crate::init();
+        let kem = Kem::new(Algorithm::MlKem768)?;
+        let run = || -> Result<_> {
+            let (pk, sk) = kem.keypair()?;
+            let (ct, ss) = kem.encapsulate(&pk)?;
+            Ok((pk, sk, ct, ss))
+        };
+        let seed = [1u8; 48];
+        let (pk1, sk1, ct1, ss1) = with_deterministic_rng(&seed, run)?;
+        let (pk2, sk2, ct2, ss2) = with_deterministic_rng(&seed, run)?;
+        assert_eq!(pk1, pk2);
+        assert_eq!(sk1, sk2);
+        assert_eq!(ct1, ct2);
+        assert_eq!(ss1, ss2);
+        Ok(())
+    }
+
+    #[cfg(all(feature = "deterministic_rng", feature = "ml_dsa"))]
+    #[test]
+    fn test_deterministic_sig() -> Result<()> {
+        use crate::sig::{Algorithm, Sig};
+        let _lock = test_lock();
+        crate::init();
+        let sig = Sig::new(Algorithm::MlDsa65)?;
+        let message = [0u8; 100];
+        let run = || -> Result<_> {
+            let (pk, sk) = sig.keypair()?;
+            let signature = sig.sign(&message, &sk)?;
+            Ok((pk, signature))
+        };
+        let seed = [2u8; 48];
+        let (pk1, sig1) = with_deterministic_rng(&seed, run)?;
+        let (pk2, sig2) = with_deterministic_rng(&seed, run)?;
+        assert_eq!(pk1, pk2);
+        assert_eq!(sig1, sig2);
+        Ok(())
+    }
+}
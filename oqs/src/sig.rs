@@ -11,6 +11,8 @@ use cstr_core::CStr;
 #[cfg(feature = "std")]
 use std::ffi::CStr;
 
+use const_oid::ObjectIdentifier;
+
 use crate::ffi::sig as ffi;
 use crate::newtype_buffer;
 use crate::*;
@@ -19,7 +21,7 @@ use crate::*;
 use serde::{Deserialize, Serialize};
 
 newtype_buffer!(PublicKey, PublicKeyRef);
-newtype_buffer!(SecretKey, SecretKeyRef);
+newtype_buffer!(secret SecretKey, SecretKeyRef);
 newtype_buffer!(Signature, SignatureRef);
 
 /// Message type
@@ -28,7 +30,7 @@ pub type Message = [u8];
 pub type CtxStr = [u8];
 
 macro_rules! implement_sigs {
-    { $(($feat: literal) $sig: ident: $oqs_id: ident),* $(,)? } => (
+    { $(($feat: literal) $sig: ident: $alg: ident),* $(,)? } => (
         /// Supported algorithms by liboqs
         ///
         /// They may not all be enabled
@@ -43,180 +45,447 @@ macro_rules! implement_sigs {
             )*
         }
 
+        impl Algorithm {
+            /// All algorithms, including those that are not enabled
+            pub const ALL: &'static [Algorithm] = &[$(Algorithm::$sig,)*];
+        }
+
         fn algorithm_to_id(algorithm: Algorithm) -> *const libc::c_char {
-            let id: &[u8] = match algorithm {
-                $(
-                    Algorithm::$sig => &ffi::$oqs_id[..],
-                )*
+            let id: &[u8] = paste::paste! {
+                match algorithm {
+                    $(
+                        Algorithm::$sig => &ffi::[<OQS_SIG_alg_ $alg>][..],
+                    )*
+                }
             };
             id as *const _ as *const libc::c_char
         }
 
-        $(
-            #[cfg(test)]
-            #[allow(non_snake_case)]
-            mod $sig {
-                use super::*;
+        /// The `oqsconfig.h` macro that enables the algorithm
+        fn algorithm_enable_macro(algorithm: Algorithm) -> &'static str {
+            match algorithm {
+                $(
+                    Algorithm::$sig => concat!("OQS_ENABLE_SIG_", stringify!($alg)),
+                )*
+            }
+        }
 
-                #[test]
+        $(
+            paste::paste! {
+                #[doc = "Marker type for [`Algorithm::" $sig "`], see [`SigAlgorithm`]"]
                 #[cfg(feature = $feat)]
-                fn test_signing() -> Result<()> {
-                    crate::init();
-                    let message = [0u8; 100];
-                    let sig = Sig::new(Algorithm::$sig)?;
-                    let (pk, sk) = sig.keypair()?;
-                    let signature = sig.sign(&message, &sk)?;
-                    sig.verify(&message, &signature, &pk)
-                }
+                #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+                pub struct $sig;
 
-                #[test]
                 #[cfg(feature = $feat)]
-                fn test_signing_with_empty_context_string() -> Result<()> {
-                    crate::init();
-                    let message = [0u8; 100];
-                    let ctx_str: [u8; 0] = [];
-                    let sig = Sig::new(Algorithm::$sig)?;
-                    let (pk, sk) = sig.keypair()?;
-                    let signature = sig.sign_with_ctx_str(&message, &ctx_str, &sk)?;
-                    sig.verify_with_ctx_str(&message, &signature, &ctx_str, &pk)
+                impl SigAlgorithm for $sig {
+                    const ALGORITHM: Algorithm = Algorithm::$sig;
+                    type PublicKeyBytes = [u8; Algorithm::$sig.info().length_public_key];
+                    type SecretKeyBytes = [u8; Algorithm::$sig.info().length_secret_key];
+                    type SignatureBytes = [u8; Algorithm::$sig.info().length_signature];
                 }
+            }
+        )*
 
-                #[test]
-                #[cfg(feature = $feat)]
-                fn test_signing_with_nonempty_context_string() -> Result<()> {
-                    crate::init();
-                    let message = [0u8; 100];
-                    let ctx_str = [0u8; 100];
-                    let sig = Sig::new(Algorithm::$sig)?;
-                    let (pk, sk) = sig.keypair()?;
-                    if sig.has_ctx_str_support() {
+        #[cfg(test)]
+        mod test {
+            use super::*;
+
+            $(
+                #[allow(non_snake_case)]
+                mod $sig {
+                    use super::*;
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_signing() -> Result<()> {
+                        crate::init();
+                        let message = [0u8; 100];
+                        let sig = Sig::new(Algorithm::$sig)?;
+                        let (pk, sk) = sig.keypair()?;
+                        let signature = sig.sign(&message, &sk)?;
+                        sig.verify(&message, &signature, &pk)
+                    }
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_signing_with_empty_context_string() -> Result<()> {
+                        crate::init();
+                        let message = [0u8; 100];
+                        let ctx_str: [u8; 0] = [];
+                        let sig = Sig::new(Algorithm::$sig)?;
+                        let (pk, sk) = sig.keypair()?;
                         let signature = sig.sign_with_ctx_str(&message, &ctx_str, &sk)?;
                         sig.verify_with_ctx_str(&message, &signature, &ctx_str, &pk)
-                    } else {
-                        let sig_result = sig.sign_with_ctx_str(&message, &ctx_str, &sk);
-                        // Expect a generic error
-                        let sig_result: Result<()> = match sig_result {
-                            Err(Error::Error) => Ok(()),
-                            Ok(_) => Err(Error::Error),
-                            Err(e) => Err(e)
-                        };
-                        if sig_result.is_ok() {
-                            // get a valid signature with which to test verify
-                            let signature = sig.sign(&message, &sk)?;
-                            // Expect a generic error
-                            match sig.verify_with_ctx_str(&message, &signature, &ctx_str, &pk) {
-                                Err(Error::Error) => Ok(()),
+                    }
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_signing_with_nonempty_context_string() -> Result<()> {
+                        crate::init();
+                        let message = [0u8; 100];
+                        let ctx_str = [0u8; 100];
+                        let sig = Sig::new(Algorithm::$sig)?;
+                        let (pk, sk) = sig.keypair()?;
+                        if sig.has_ctx_str_support() {
+                            let signature = sig.sign_with_ctx_str(&message, &ctx_str, &sk)?;
+                            sig.verify_with_ctx_str(&message, &signature, &ctx_str, &pk)
+                        } else {
+                            let sig_result = sig.sign_with_ctx_str(&message, &ctx_str, &sk);
+                            // Expect signing to fail
+                            let sig_result: Result<()> = match sig_result {
+                                Err(Error::Failed { operation: Operation::Sign, .. }) => Ok(()),
                                 Ok(_) => Err(Error::Error),
                                 Err(e) => Err(e)
+                            };
+                            if sig_result.is_ok() {
+                                // get a valid signature with which to test verify
+                                let signature = sig.sign(&message, &sk)?;
+                                // Expect verification to fail
+                                match sig.verify_with_ctx_str(&message, &signature, &ctx_str, &pk) {
+                                    Err(Error::VerificationFailed { .. }) => Ok(()),
+                                    Ok(_) => Err(Error::Error),
+                                    Err(e) => Err(e)
 
+                                }
+                            } else {
+                                sig_result
                             }
+                        }
+                    }
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_signing_into() -> Result<()> {
+                        crate::init();
+                        let message = [0u8; 100];
+                        let sig = Sig::new(Algorithm::$sig)?;
+                        let mut pk = alloc::vec![0; sig.length_public_key()];
+                        let mut sk = alloc::vec![0; sig.length_secret_key()];
+                        sig.keypair_into(&mut pk, &mut sk)?;
+                        let pk = sig.public_key_from_bytes(&pk).unwrap();
+                        let sk = sig.secret_key_from_bytes(&sk).unwrap();
+                        let mut signature = alloc::vec![0; sig.length_signature()];
+                        let len = sig.sign_into(&message, sk, &mut signature)?;
+                        let signature = sig.signature_from_bytes(&signature[..len]).unwrap();
+                        sig.verify(&message, signature, pk)?;
+                        assert!(matches!(
+                            sig.verify(&[1u8; 100], signature, pk),
+                            Err(Error::VerificationFailed { .. })
+                        ));
+                        if sig.has_ctx_str_support() {
+                            let mut signature = alloc::vec![0; sig.length_signature() + 1];
+                            let len = sig.sign_with_ctx_str_into(
+                                &message,
+                                b"context",
+                                sk,
+                                &mut signature,
+                            )?;
+                            let signature = sig.signature_from_bytes(&signature[..len]).unwrap();
+                            sig.verify_with_ctx_str(&message, signature, b"context", pk)?;
+                        }
+
+                        let mut short = alloc::vec![0; sig.length_signature() - 1];
+                        let error = sig.sign_into(&message, sk, &mut short).unwrap_err();
+                        assert!(matches!(
+                            error,
+                            Error::WrongLength { operation: Operation::Sign, expected, actual, .. }
+                                if expected == sig.length_signature() && actual == short.len()
+                        ));
+                        let mut short = alloc::vec![0; sig.length_public_key() - 1];
+                        let mut sk = alloc::vec![0; sig.length_secret_key()];
+                        assert!(matches!(
+                            sig.keypair_into(&mut short, &mut sk),
+                            Err(Error::WrongLength { operation: Operation::Keypair, .. })
+                        ));
+                        Ok(())
+                    }
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_streaming() -> Result<()> {
+                        crate::init();
+                        let message = [3u8; 1000];
+                        let sig = Sig::new(Algorithm::$sig)?;
+                        let (pk, sk) = sig.keypair()?;
+                        let mut signer = sig.signer(&sk);
+                        for chunk in message.chunks(300) {
+                            signer.update(chunk);
+                        }
+                        let signature = signer.finalize()?;
+                        sig.verify(&message, &signature, &pk)?;
+
+                        let signature = sig.sign(&message, &sk)?;
+                        let mut verifier = sig.verifier(&pk, &signature);
+                        for chunk in message.chunks(300) {
+                            verifier.update(chunk);
+                        }
+                        verifier.finalize()?;
+                        let mut verifier = sig.verifier(&pk, &signature);
+                        verifier.update(&message[1..]);
+                        assert!(verifier.finalize().is_err());
+
+                        if sig.has_ctx_str_support() {
+                            let mut signer = sig.signer_with_ctx_str(b"context", &sk);
+                            signer.update(&message);
+                            let signature = signer.finalize()?;
+                            sig.verify_with_ctx_str(&message, &signature, b"context", &pk)?;
+                            let mut verifier =
+                                sig.verifier_with_ctx_str(b"context", &pk, &signature);
+                            verifier.update(&message);
+                            verifier.finalize()?;
+                        }
+                        Ok(())
+                    }
+
+                    #[test]
+                    #[cfg(all(feature = $feat, feature = "std"))]
+                    fn test_streaming_io() -> Result<()> {
+                        crate::init();
+                        let message = [3u8; 1000];
+                        let sig = Sig::new(Algorithm::$sig)?;
+                        let (pk, sk) = sig.keypair()?;
+                        let mut signer = sig.signer(&sk);
+                        std::io::copy(&mut &message[..], &mut signer).unwrap();
+                        let signature = signer.finalize()?;
+                        let mut verifier = sig.verifier(&pk, &signature);
+                        std::io::copy(&mut &message[..], &mut verifier).unwrap();
+                        verifier.finalize()
+                    }
+
+                    #[test]
+                    #[cfg(all(feature = $feat, feature = "deterministic_rng", feature = "std"))]
+                    fn test_kat() -> Result<()> {
+                        crate::init();
+                        crate::kat::check_sig(Algorithm::$sig)
+                    }
+
+                    #[test]
+                    #[cfg(all(feature = $feat, feature = "signature"))]
+                    fn test_signature_traits() -> core::result::Result<(), ::signature::Error> {
+                        use ::signature::{Keypair, SignatureEncoding, Signer, Verifier};
+                        crate::init();
+                        let message = [0u8; 100];
+                        let signing_key = SigningKey::generate(Algorithm::$sig).unwrap();
+                        let signature = signing_key.try_sign(&message)?;
+                        let decoded = Signature::try_from(signature.to_vec().as_slice())?;
+                        let verifying_key = signing_key.verifying_key();
+                        verifying_key.verify(&message, &decoded)?;
+                        assert!(verifying_key.verify(&[1u8; 100], &decoded).is_err());
+                        let pk = verifying_key.public_key();
+                        assert_eq!(VerifyingKey::from_bytes(Algorithm::$sig, &pk).unwrap(), verifying_key);
+                        Ok(())
+                    }
+
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_typed() -> Result<()> {
+                        use crate::sig::typed::{self, SigAlgorithm};
+                        type A = crate::sig::$sig;
+                        crate::init();
+
+                        let sig = Sig::new(A::ALGORITHM)?;
+                        assert_eq!(typed::PublicKey::<A>::LENGTH, sig.length_public_key());
+                        assert_eq!(typed::SecretKey::<A>::LENGTH, sig.length_secret_key());
+                        assert_eq!(typed::Signature::<A>::MAX_LENGTH, sig.length_signature());
+                        let message = [0u8; 100];
+                        let (pk, sk) = A::keypair()?;
+                        let signature = A::sign(&message, &sk)?;
+                        A::verify(&message, &signature, &pk)?;
+                        // typed buffers also work with the dynamic API
+                        sig.verify(&message, &signature, &pk)?;
+                        let signature = typed::Signature::<A>::from_bytes(signature.as_ref())?;
+                        A::verify(&message, &signature, &pk)?;
+                        assert!(A::verify(&[1u8; 100], &signature, &pk).is_err());
+                        if sig.has_ctx_str_support() {
+                            let signature = A::sign_with_ctx_str(&message, b"context", &sk)?;
+                            A::verify_with_ctx_str(&message, &signature, b"context", &pk)?;
+                        }
+                        Ok(())
+                    }
+
+                    #[test]
+                    #[cfg(all(feature = $feat, feature = "pkcs8"))]
+                    fn test_pkcs8() -> Result<()> {
+                        use pkcs8::LineEnding;
+                        crate::init();
+
+                        let sig = Sig::new(Algorithm::$sig)?;
+                        let (pk, sk) = sig.keypair()?;
+                        if Algorithm::$sig.oid().is_none() {
+                            assert!(matches!(sig.public_key_to_der(&pk), Err(Error::Unsupported)));
+                            return Ok(());
+                        }
+                        let der = sig.public_key_to_der(&pk)?;
+                        assert_eq!(sig.public_key_from_der(der.as_bytes())?, pk);
+                        let pem = sig.public_key_to_pem(&pk, LineEnding::LF)?;
+                        assert_eq!(sig.public_key_from_pem(&pem)?, pk);
+                        let der = sig.secret_key_to_der(&sk, &pk)?;
+                        assert_eq!(sig.secret_key_from_der(der.as_bytes())?, sk);
+                        let pem = sig.secret_key_to_pem(&sk, &pk, LineEnding::LF)?;
+                        assert_eq!(sig.secret_key_from_pem(&pem)?, sk);
+                        Ok(())
+                    }
+
+                    #[test]
+                    fn test_enabled() {
+                        crate::init();
+                        if cfg!(feature = $feat) {
+                            assert!(Algorithm::$sig.is_enabled());
                         } else {
-                            sig_result
+                            assert!(!Algorithm::$sig.is_enabled())
                         }
                     }
-                }
 
-                #[test]
-                fn test_enabled() {
-                    crate::init();
-                    if cfg!(feature = $feat) {
-                        assert!(Algorithm::$sig.is_enabled());
-                    } else {
-                        assert!(!Algorithm::$sig.is_enabled())
+                    #[test]
+                    fn test_name() {
+                        let algo = Algorithm::$sig;
+                        // Just make sure the name impl does not panic or crash.
+                        let name = algo.name();
+
+                        #[cfg(feature = "std")]
+                        assert_eq!(name, algo.to_string());
+
+                        // ... And actually contains something.
+                        assert!(!name.is_empty());
                     }
-                }
 
-                #[test]
-                fn test_name() {
-                    let algo = Algorithm::$sig;
-                    // Just make sure the name impl does not panic or crash.
-                    let name = algo.name();
+                    #[test]
+                    #[cfg(feature = $feat)]
+                    fn test_info() -> Result<()> {
+                        crate::init();
+                        let sig = Sig::new(Algorithm::$sig)?;
+                        let info = Algorithm::$sig.info();
+                        assert_eq!(info.claimed_nist_level, sig.claimed_nist_level());
+                        assert_eq!(info.is_euf_cma, sig.is_euf_cma());
+                        assert_eq!(info.has_ctx_str_support, sig.has_ctx_str_support());
+                        assert_eq!(info.length_public_key, sig.length_public_key());
+                        assert_eq!(info.length_secret_key, sig.length_secret_key());
+                        assert_eq!(info.length_signature, sig.length_signature());
+                        Ok(())
+                    }
 
-                    #[cfg(feature = "std")]
-                    assert_eq!(name, algo.to_string());
+                    #[test]
+                    fn test_from_str() {
+                        let algorithm = Algorithm::$sig;
+                        assert_eq!(algorithm.name().parse::<Algorithm>().ok(), Some(algorithm));
+                        assert!(Algorithm::iter().any(|alg| alg == algorithm));
+                        assert_eq!(
+                            Algorithm::enabled().any(|alg| alg == algorithm),
+                            algorithm.is_enabled()
+                        );
+                    }
 
-                    // ... And actually contains something.
-                    assert!(!name.is_empty());
-                }
+                    #[test]
+                    fn test_from_oid() {
+                        if let Some(oid) = Algorithm::$sig.oid() {
+                            assert_eq!(Algorithm::from_oid(oid), Some(Algorithm::$sig));
+                        }
+                    }
 
-                #[test]
-                fn test_get_algorithm_back() {
-                    let algorithm = Algorithm::$sig;
-                    if algorithm.is_enabled() {
-                        let sig = Sig::new(algorithm).unwrap();
-                        assert_eq!(algorithm, sig.algorithm());
+                    #[test]
+                    fn test_get_algorithm_back() {
+                        let algorithm = Algorithm::$sig;
+                        if algorithm.is_enabled() {
+                            let sig = Sig::new(algorithm).unwrap();
+                            assert_eq!(algorithm, sig.algorithm());
+                        }
+                    }
+
+                    #[test]
+                    fn test_version() {
+                        if let Ok(sig) = Sig::new(Algorithm::$sig) {
+                            // Just make sure the version can be called without panic
+                            let version = sig.version();
+                            // ... And actually contains something.
+                            assert!(!version.is_empty());
+                        }
                     }
                 }
+            )*
 
-                #[test]
-                fn test_version() {
-                    if let Ok(sig) = Sig::new(Algorithm::$sig) {
-                        // Just make sure the version can be called without panic
-                        let version = sig.version();
-                        // ... And actually contains something.
-                        assert!(!version.is_empty());
+            #[test]
+            fn test_liboqs_identifiers() {
+                crate::init();
+                // The liboqs release that oqs-sys bundles, and its algorithms we don't support.
+                // Newer releases may add algorithms, so coverage is only checked for this one.
+                const PINNED_VERSION: &str = "0.13.0";
+                const UNSUPPORTED: &[&str] = &["Falcon-padded-512", "Falcon-padded-1024"];
+                let pinned = crate::version() == PINNED_VERSION;
+                let count = unsafe { ffi::OQS_SIG_alg_count() };
+                for i in 0..count {
+                    let id = unsafe { CStr::from_ptr(ffi::OQS_SIG_alg_identifier(i as usize)) };
+                    let name = id.to_str().unwrap();
+                    match name.parse::<Algorithm>() {
+                        Ok(algorithm) => assert_eq!(algorithm.name(), name),
+                        Err(_) => assert!(
+                            !pinned || UNSUPPORTED.contains(&name),
+                            "{name} has no Algorithm variant"
+                        ),
                     }
                 }
+                assert!(Algorithm::enabled().all(|alg| alg.is_enabled()));
+                assert!("unknown".parse::<Algorithm>().is_err());
             }
-        )*
+        }
     )
 }
 
 implement_sigs! {
-    ("cross") CrossRsdp128Balanced: OQS_SIG_alg_cross_rsdp_128_balanced,
-    ("cross") CrossRsdp128Fast: OQS_SIG_alg_cross_rsdp_128_fast,
-    ("cross") CrossRsdp128Small: OQS_SIG_alg_cross_rsdp_128_small,
-    ("cross") CrossRsdp192Balanced: OQS_SIG_alg_cross_rsdp_192_balanced,
-    ("cross") CrossRsdp192Fast: OQS_SIG_alg_cross_rsdp_192_fast,
-    ("cross") CrossRsdp192Small: OQS_SIG_alg_cross_rsdp_192_small,
-    ("cross") CrossRsdp256Balanced: OQS_SIG_alg_cross_rsdp_256_balanced,
-    ("cross") CrossRsdp256Fast: OQS_SIG_alg_cross_rsdp_256_fast,
-    ("cross") CrossRsdp256Small: OQS_SIG_alg_cross_rsdp_256_small,
-    ("cross") CrossRsdpg128Balanced: OQS_SIG_alg_cross_rsdpg_128_balanced,
-    ("cross") CrossRsdpg128Fast: OQS_SIG_alg_cross_rsdpg_128_fast,
-    ("cross") CrossRsdpg128Small: OQS_SIG_alg_cross_rsdpg_128_small,
-    ("cross") CrossRsdpg192Balanced: OQS_SIG_alg_cross_rsdpg_192_balanced,
-    ("cross") CrossRsdpg192Fast: OQS_SIG_alg_cross_rsdpg_192_fast,
-    ("cross") CrossRsdpg192Small: OQS_SIG_alg_cross_rsdpg_192_small,
-    ("cross") CrossRsdpg256Balanced: OQS_SIG_alg_cross_rsdpg_256_balanced,
-    ("cross") CrossRsdpg256Fast: OQS_SIG_alg_cross_rsdpg_256_fast,
-    ("cross") CrossRsdpg256Small: OQS_SIG_alg_cross_rsdpg_256_small,
-    ("dilithium") Dilithium2: OQS_SIG_alg_dilithium_2,
-    ("dilithium") Dilithium3: OQS_SIG_alg_dilithium_3,
-    ("dilithium") Dilithium5: OQS_SIG_alg_dilithium_5,
-    ("falcon") Falcon512: OQS_SIG_alg_falcon_512,
-    ("falcon") Falcon1024: OQS_SIG_alg_falcon_1024,
-    ("mayo") Mayo1: OQS_SIG_alg_mayo_1,
-    ("mayo") Mayo2: OQS_SIG_alg_mayo_2,
-    ("mayo") Mayo3: OQS_SIG_alg_mayo_3,
-    ("mayo") Mayo5: OQS_SIG_alg_mayo_5,
-    ("ml_dsa") MlDsa44: OQS_SIG_alg_ml_dsa_44,
-    ("ml_dsa") MlDsa65: OQS_SIG_alg_ml_dsa_65,
-    ("ml_dsa") MlDsa87: OQS_SIG_alg_ml_dsa_87,
-    ("sphincs") SphincsSha2128fSimple: OQS_SIG_alg_sphincs_sha2_128f_simple,
-    ("sphincs") SphincsSha2128sSimple: OQS_SIG_alg_sphincs_sha2_128s_simple,
-    ("sphincs") SphincsSha2192fSimple: OQS_SIG_alg_sphincs_sha2_192f_simple,
-    ("sphincs") SphincsSha2192sSimple: OQS_SIG_alg_sphincs_sha2_192s_simple,
-    ("sphincs") SphincsSha2256fSimple: OQS_SIG_alg_sphincs_sha2_256f_simple,
-    ("sphincs") SphincsSha2256sSimple: OQS_SIG_alg_sphincs_sha2_256s_simple,
-    ("sphincs") SphincsShake128fSimple: OQS_SIG_alg_sphincs_shake_128f_simple,
-    ("sphincs") SphincsShake128sSimple: OQS_SIG_alg_sphincs_shake_128s_simple,
-    ("sphincs") SphincsShake192fSimple: OQS_SIG_alg_sphincs_shake_192f_simple,
-    ("sphincs") SphincsShake192sSimple: OQS_SIG_alg_sphincs_shake_192s_simple,
-    ("sphincs") SphincsShake256fSimple: OQS_SIG_alg_sphincs_shake_256f_simple,
-    ("sphincs") SphincsShake256sSimple: OQS_SIG_alg_sphincs_shake_256s_simple,
-    ("uov") UovOvIs: OQS_SIG_alg_uov_ov_Is,
-    ("uov") UovOvIp: OQS_SIG_alg_uov_ov_Ip,
-    ("uov") UovOvIII: OQS_SIG_alg_uov_ov_III,
-    ("uov") UovOvV: OQS_SIG_alg_uov_ov_V,
-    ("uov") UovOvIsPkc: OQS_SIG_alg_uov_ov_Is_pkc,
-    ("uov") UovOvIpPkc: OQS_SIG_alg_uov_ov_Ip_pkc,
-    ("uov") UovOvIIIPkc: OQS_SIG_alg_uov_ov_III_pkc,
-    ("uov") UovOvVPkc: OQS_SIG_alg_uov_ov_V_pkc,
-    ("uov") UovOvIsPkcSkc: OQS_SIG_alg_uov_ov_Is_pkc_skc,
-    ("uov") UovOvIpPkcSkc: OQS_SIG_alg_uov_ov_Ip_pkc_skc,
-    ("uov") UovOvIIIPkcSkc: OQS_SIG_alg_uov_ov_III_pkc_skc,
-    ("uov") UovOvVPkcSkc: OQS_SIG_alg_uov_ov_V_pkc_skc,
+    ("cross") CrossRsdp128Balanced: cross_rsdp_128_balanced,
+    ("cross") CrossRsdp128Fast: cross_rsdp_128_fast,
+    ("cross") CrossRsdp128Small: cross_rsdp_128_small,
+    ("cross") CrossRsdp192Balanced: cross_rsdp_192_balanced,
+    ("cross") CrossRsdp192Fast: cross_rsdp_192_fast,
+    ("cross") CrossRsdp192Small: cross_rsdp_192_small,
+    ("cross") CrossRsdp256Balanced: cross_rsdp_256_balanced,
+    ("cross") CrossRsdp256Fast: cross_rsdp_256_fast,
+    ("cross") CrossRsdp256Small: cross_rsdp_256_small,
+    ("cross") CrossRsdpg128Balanced: cross_rsdpg_128_balanced,
+    ("cross") CrossRsdpg128Fast: cross_rsdpg_128_fast,
+    ("cross") CrossRsdpg128Small: cross_rsdpg_128_small,
+    ("cross") CrossRsdpg192Balanced: cross_rsdpg_192_balanced,
+    ("cross") CrossRsdpg192Fast: cross_rsdpg_192_fast,
+    ("cross") CrossRsdpg192Small: cross_rsdpg_192_small,
+    ("cross") CrossRsdpg256Balanced: cross_rsdpg_256_balanced,
+    ("cross") CrossRsdpg256Fast: cross_rsdpg_256_fast,
+    ("cross") CrossRsdpg256Small: cross_rsdpg_256_small,
+    ("dilithium") Dilithium2: dilithium_2,
+    ("dilithium") Dilithium3: dilithium_3,
+    ("dilithium") Dilithium5: dilithium_5,
+    ("falcon") Falcon512: falcon_512,
+    ("falcon") Falcon1024: falcon_1024,
+    ("mayo") Mayo1: mayo_1,
+    ("mayo") Mayo2: mayo_2,
+    ("mayo") Mayo3: mayo_3,
+    ("mayo") Mayo5: mayo_5,
+    ("ml_dsa") MlDsa44: ml_dsa_44,
+    ("ml_dsa") MlDsa65: ml_dsa_65,
+    ("ml_dsa") MlDsa87: ml_dsa_87,
+    ("sphincs") SphincsSha2128fSimple: sphincs_sha2_128f_simple,
+    ("sphincs") SphincsSha2128sSimple: sphincs_sha2_128s_simple,
+    ("sphincs") SphincsSha2192fSimple: sphincs_sha2_192f_simple,
+    ("sphincs") SphincsSha2192sSimple: sphincs_sha2_192s_simple,
+    ("sphincs") SphincsSha2256fSimple: sphincs_sha2_256f_simple,
+    ("sphincs") SphincsSha2256sSimple: sphincs_sha2_256s_simple,
+    ("sphincs") SphincsShake128fSimple: sphincs_shake_128f_simple,
+    ("sphincs") SphincsShake128sSimple: sphincs_shake_128s_simple,
+    ("sphincs") SphincsShake192fSimple: sphincs_shake_192f_simple,
+    ("sphincs") SphincsShake192sSimple: sphincs_shake_192s_simple,
+    ("sphincs") SphincsShake256fSimple: sphincs_shake_256f_simple,
+    ("sphincs") SphincsShake256sSimple: sphincs_shake_256s_simple,
+    ("uov") UovOvIs: uov_ov_Is,
+    ("uov") UovOvIp: uov_ov_Ip,
+    ("uov") UovOvIII: uov_ov_III,
+    ("uov") UovOvV: uov_ov_V,
+    ("uov") UovOvIsPkc: uov_ov_Is_pkc,
+    ("uov") UovOvIpPkc: uov_ov_Ip_pkc,
+    ("uov") UovOvIIIPkc: uov_ov_III_pkc,
+    ("uov") UovOvVPkc: uov_ov_V_pkc,
+    ("uov") UovOvIsPkcSkc: uov_ov_Is_pkc_skc,
+    ("uov") UovOvIpPkcSkc: uov_ov_Ip_pkc_skc,
+    ("uov") UovOvIIIPkcSkc: uov_ov_III_pkc_skc,
+    ("uov") UovOvVPkcSkc: uov_ov_V_pkc_skc,
 }
 
 impl Algorithm {
@@ -241,6 +510,204 @@ impl Algorithm {
         let id = unsafe { CStr::from_ptr(self.to_id()) };
         id.to_str().expect("OQS algorithm names must be UTF-8")
     }
+
+    /// Iterate over all algorithms, including those that are not enabled
+    pub fn iter() -> impl Iterator<Item = Algorithm> {
+        Self::ALL.iter().copied()
+    }
+
+    /// Iterate over the algorithms that are enabled in the linked version of liboqs
+    pub fn enabled() -> impl Iterator<Item = Algorithm> {
+        Self::iter().filter(|alg| alg.is_enabled())
+    }
+
+    /// An estimate of the implementation liboqs selects for this algorithm on this CPU
+    ///
+    /// See [`cpu::summary`](crate::cpu::summary).
+    pub fn implementation(self) -> crate::cpu::Implementation {
+        crate::cpu::select(algorithm_enable_macro(self))
+    }
+
+    /// Object identifier of the algorithm, if one has been assigned
+    ///
+    /// These are the NIST OIDs of ML-DSA, and the OIDs that oqs-provider
+    /// uses for Falcon and SPHINCS+.
+    /// SPHINCS+ is not the same as SLH-DSA, so it does not use the OIDs of SLH-DSA.
+    pub fn oid(self) -> Option<ObjectIdentifier> {
+        let oid = match self {
+            Algorithm::MlDsa44 => "2.16.840.1.101.3.4.3.17",
+            Algorithm::MlDsa65 => "2.16.840.1.101.3.4.3.18",
+            Algorithm::MlDsa87 => "2.16.840.1.101.3.4.3.19",
+            Algorithm::Falcon512 => "1.3.9999.3.11",
+            Algorithm::Falcon1024 => "1.3.9999.3.14",
+            Algorithm::SphincsSha2128fSimple => "1.3.9999.6.4.13",
+            Algorithm::SphincsSha2128sSimple => "1.3.9999.6.4.16",
+            Algorithm::SphincsSha2192fSimple => "1.3.9999.6.5.10",
+            Algorithm::SphincsSha2256fSimple => "1.3.9999.6.6.10",
+            Algorithm::SphincsShake128fSimple => "1.3.9999.6.7.13",
+            _ => return None,
+        };
+        Some(ObjectIdentifier::new_unwrap(oid))
+    }
+
+    /// Look up the algorithm with the object identifier `oid`
+    pub fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        Self::iter().find(|alg| alg.oid() == Some(oid))
+    }
+}
+
+/// Parse an algorithm from its liboqs identifier, such as `"ML-DSA-65"`
+impl core::str::FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        Self::iter()
+            .find(|alg| alg.name() == name)
+            .ok_or(Error::UnknownAlgorithm)
+    }
+}
+
+/// Family of a signature algorithm
+///
+/// Optional support for `serde` if that feature is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Family {
+    /// CROSS
+    Cross,
+    /// CRYSTALS-Dilithium
+    Dilithium,
+    /// Falcon
+    Falcon,
+    /// MAYO
+    Mayo,
+    /// ML-DSA (FIPS 204)
+    MlDsa,
+    /// SPHINCS+
+    Sphincs,
+    /// UOV
+    Uov,
+}
+
+/// Properties of a signature algorithm that are known without a [`Sig`]
+///
+/// See [`Algorithm::info`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct AlgorithmInfo {
+    /// Family of the algorithm
+    pub family: Family,
+    /// Standardization status of the algorithm
+    pub standardization: Standardization,
+    /// NIST security level claimed by the algorithm
+    pub claimed_nist_level: u8,
+    /// Is the algorithm EUF-CMA secure
+    pub is_euf_cma: bool,
+    /// Does the algorithm support signing with a context string
+    pub has_ctx_str_support: bool,
+    /// Length of a public key
+    pub length_public_key: usize,
+    /// Length of a secret key
+    pub length_secret_key: usize,
+    /// Maximum length of a signature
+    pub length_signature: usize,
+}
+
+impl Algorithm {
+    /// Returns the properties of the algorithm
+    ///
+    /// This does not need the algorithm to be enabled, and can be used in constants:
+    /// ```rust
+    /// use oqs::sig::{Algorithm, AlgorithmInfo};
+    /// const INFO: AlgorithmInfo = Algorithm::MlDsa65.info();
+    /// assert_eq!(INFO.length_signature, 3309);
+    /// ```
+    pub const fn info(self) -> AlgorithmInfo {
+        macro_rules! info {
+            ($family: ident, $status: ident, $level: literal, $ctx_str: literal,
+             $pk: literal, $sk: literal, $sig: literal) => {
+                AlgorithmInfo {
+                    family: Family::$family,
+                    standardization: Standardization::$status,
+                    claimed_nist_level: $level,
+                    is_euf_cma: true,
+                    has_ctx_str_support: $ctx_str,
+                    length_public_key: $pk,
+                    length_secret_key: $sk,
+                    length_signature: $sig,
+                }
+            };
+        }
+        match self {
+            Algorithm::CrossRsdp128Balanced => info!(Cross, Candidate, 1, false, 77, 32, 12432),
+            Algorithm::CrossRsdp128Fast => info!(Cross, Candidate, 1, false, 77, 32, 19152),
+            Algorithm::CrossRsdp128Small => info!(Cross, Candidate, 1, false, 77, 32, 11980),
+            Algorithm::CrossRsdp192Balanced => info!(Cross, Candidate, 3, false, 115, 48, 28391),
+            Algorithm::CrossRsdp192Fast => info!(Cross, Candidate, 3, false, 115, 48, 42682),
+            Algorithm::CrossRsdp192Small => info!(Cross, Candidate, 3, false, 115, 48, 26772),
+            Algorithm::CrossRsdp256Balanced => info!(Cross, Candidate, 5, false, 153, 64, 51056),
+            Algorithm::CrossRsdp256Fast => info!(Cross, Candidate, 5, false, 153, 64, 82200),
+            Algorithm::CrossRsdp256Small => info!(Cross, Candidate, 5, false, 153, 64, 48102),
+            Algorithm::CrossRsdpg128Balanced => info!(Cross, Candidate, 1, false, 54, 32, 9236),
+            Algorithm::CrossRsdpg128Fast => info!(Cross, Candidate, 1, false, 54, 32, 12052),
+            Algorithm::CrossRsdpg128Small => info!(Cross, Candidate, 1, false, 54, 32, 8960),
+            Algorithm::CrossRsdpg192Balanced => info!(Cross, Candidate, 3, false, 83, 48, 23380),
+            Algorithm::CrossRsdpg192Fast => info!(Cross, Candidate, 3, false, 83, 48, 27404),
+            Algorithm::CrossRsdpg192Small => info!(Cross, Candidate, 3, false, 83, 48, 20452),
+            Algorithm::CrossRsdpg256Balanced => info!(Cross, Candidate, 5, false, 106, 64, 40134),
+            Algorithm::CrossRsdpg256Fast => info!(Cross, Candidate, 5, false, 106, 64, 48036),
+            Algorithm::CrossRsdpg256Small => info!(Cross, Candidate, 5, false, 106, 64, 36454),
+            Algorithm::Dilithium2 => info!(Dilithium, Deprecated, 2, false, 1312, 2528, 2420),
+            Algorithm::Dilithium3 => info!(Dilithium, Deprecated, 3, false, 1952, 4000, 3293),
+            Algorithm::Dilithium5 => info!(Dilithium, Deprecated, 5, false, 2592, 4864, 4595),
+            Algorithm::Falcon512 => info!(Falcon, Draft, 1, false, 897, 1281, 752),
+            Algorithm::Falcon1024 => info!(Falcon, Draft, 5, false, 1793, 2305, 1462),
+            Algorithm::Mayo1 => info!(Mayo, Candidate, 1, false, 1420, 24, 454),
+            Algorithm::Mayo2 => info!(Mayo, Candidate, 1, false, 4912, 24, 186),
+            Algorithm::Mayo3 => info!(Mayo, Candidate, 3, false, 2986, 32, 681),
+            Algorithm::Mayo5 => info!(Mayo, Candidate, 5, false, 5554, 40, 964),
+            Algorithm::MlDsa44 => info!(MlDsa, Standardized, 2, true, 1312, 2560, 2420),
+            Algorithm::MlDsa65 => info!(MlDsa, Standardized, 3, true, 1952, 4032, 3309),
+            Algorithm::MlDsa87 => info!(MlDsa, Standardized, 5, true, 2592, 4896, 4627),
+            Algorithm::SphincsSha2128fSimple => info!(Sphincs, Deprecated, 1, false, 32, 64, 17088),
+            Algorithm::SphincsSha2128sSimple => info!(Sphincs, Deprecated, 1, false, 32, 64, 7856),
+            Algorithm::SphincsSha2192fSimple => info!(Sphincs, Deprecated, 3, false, 48, 96, 35664),
+            Algorithm::SphincsSha2192sSimple => info!(Sphincs, Deprecated, 3, false, 48, 96, 16224),
+            Algorithm::SphincsSha2256fSimple => {
+                info!(Sphincs, Deprecated, 5, false, 64, 128, 49856)
+            }
+            Algorithm::SphincsSha2256sSimple => {
+                info!(Sphincs, Deprecated, 5, false, 64, 128, 29792)
+            }
+            Algorithm::SphincsShake128fSimple => {
+                info!(Sphincs, Deprecated, 1, false, 32, 64, 17088)
+            }
+            Algorithm::SphincsShake128sSimple => info!(Sphincs, Deprecated, 1, false, 32, 64, 7856),
+            Algorithm::SphincsShake192fSimple => {
+                info!(Sphincs, Deprecated, 3, false, 48, 96, 35664)
+            }
+            Algorithm::SphincsShake192sSimple => {
+                info!(Sphincs, Deprecated, 3, false, 48, 96, 16224)
+            }
+            Algorithm::SphincsShake256fSimple => {
+                info!(Sphincs, Deprecated, 5, false, 64, 128, 49856)
+            }
+            Algorithm::SphincsShake256sSimple => {
+                info!(Sphincs, Deprecated, 5, false, 64, 128, 29792)
+            }
+            Algorithm::UovOvIs => info!(Uov, Candidate, 1, false, 412160, 348704, 96),
+            Algorithm::UovOvIp => info!(Uov, Candidate, 1, false, 278432, 237896, 128),
+            Algorithm::UovOvIII => info!(Uov, Candidate, 3, false, 1225440, 1044320, 200),
+            Algorithm::UovOvV => info!(Uov, Candidate, 5, false, 2869440, 2436704, 260),
+            Algorithm::UovOvIsPkc => info!(Uov, Candidate, 1, false, 66576, 348704, 96),
+            Algorithm::UovOvIpPkc => info!(Uov, Candidate, 1, false, 43576, 237896, 128),
+            Algorithm::UovOvIIIPkc => info!(Uov, Candidate, 3, false, 189232, 1044320, 200),
+            Algorithm::UovOvVPkc => info!(Uov, Candidate, 5, false, 446992, 2436704, 260),
+            Algorithm::UovOvIsPkcSkc => info!(Uov, Candidate, 1, false, 66576, 32, 96),
+            Algorithm::UovOvIpPkcSkc => info!(Uov, Candidate, 1, false, 43576, 32, 128),
+            Algorithm::UovOvIIIPkcSkc => info!(Uov, Candidate, 3, false, 189232, 32, 200),
+            Algorithm::UovOvVPkcSkc => info!(Uov, Candidate, 5, false, 446992, 32, 260),
+        }
+    }
 }
 
 /// Signature scheme
@@ -323,9 +790,13 @@ impl Sig {
     }
 
     /// Does this algorithm support signing with a context string?
+    ///
+    /// If not, [`sign_with_ctx_str`](Sig::sign_with_ctx_str) only accepts an empty context string.
     pub fn has_ctx_str_support(&self) -> bool {
         let sig = unsafe { self.sig.as_ref() };
         sig.sig_with_ctx_support
+            && sig.sign_with_ctx_str.is_some()
+            && sig.verify_with_ctx_str.is_some()
     }
 
     /// Length of the public key
@@ -376,7 +847,7 @@ impl Sig {
     /// Generate a new keypair
     pub fn keypair(&self) -> Result<(PublicKey, SecretKey)> {
         let sig = unsafe { self.sig.as_ref() };
-        let func = sig.keypair.unwrap();
+        let func = sig.keypair.ok_or(Error::Unsupported)?;
         let mut pk = PublicKey {
             bytes: Vec::with_capacity(sig.length_public_key),
         };
@@ -389,7 +860,7 @@ impl Sig {
             pk.bytes.set_len(sig.length_public_key);
             sk.bytes.set_len(sig.length_secret_key);
         }
-        status_to_result(status)?;
+        self.check_status(Operation::Keypair, status)?;
         Ok((pk, sk))
     }
 
@@ -400,8 +871,9 @@ impl Sig {
         sk: S,
     ) -> Result<Signature> {
         let sk = sk.into();
+        self.check_length(Operation::Sign, self.length_secret_key(), sk.len())?;
         let sig = unsafe { self.sig.as_ref() };
-        let func = sig.sign.unwrap();
+        let func = sig.sign.ok_or(Error::Unsupported)?;
         let mut sig = Signature {
             bytes: Vec::with_capacity(sig.length_signature),
         };
@@ -415,7 +887,7 @@ impl Sig {
                 sk.bytes.as_ptr(),
             )
         };
-        status_to_result(status)?;
+        self.check_status(Operation::Sign, status)?;
         // This is safe to do as it's initialised now.
         unsafe {
             sig.bytes.set_len(sig_len);
@@ -431,8 +903,9 @@ impl Sig {
         sk: S,
     ) -> Result<Signature> {
         let sk = sk.into();
+        self.check_length(Operation::Sign, self.length_secret_key(), sk.len())?;
         let sig = unsafe { self.sig.as_ref() };
-        let func = sig.sign_with_ctx_str.unwrap();
+        let func = sig.sign_with_ctx_str.ok_or(Error::Unsupported)?;
         let mut sig = Signature {
             bytes: Vec::with_capacity(sig.length_signature),
         };
@@ -456,7 +929,7 @@ impl Sig {
                 sk.bytes.as_ptr(),
             )
         };
-        status_to_result(status)?;
+        self.check_status(Operation::Sign, status)?;
         // This is safe to do as it's initialised now.
         unsafe {
             sig.bytes.set_len(sig_len);
@@ -473,13 +946,16 @@ impl Sig {
     ) -> Result<()> {
         let signature = signature.into();
         let pk = pk.into();
-        if signature.bytes.len() > self.length_signature()
-            || pk.bytes.len() != self.length_public_key()
-        {
-            return Err(Error::InvalidLength);
+        if signature.len() > self.length_signature() {
+            return Err(self.wrong_length(
+                Operation::Verify,
+                self.length_signature(),
+                signature.len(),
+            ));
         }
+        self.check_length(Operation::Verify, self.length_public_key(), pk.len())?;
         let sig = unsafe { self.sig.as_ref() };
-        let func = sig.verify.unwrap();
+        let func = sig.verify.ok_or(Error::Unsupported)?;
         let status = unsafe {
             func(
                 message.as_ptr(),
@@ -489,7 +965,7 @@ impl Sig {
                 pk.bytes.as_ptr(),
             )
         };
-        status_to_result(status)
+        self.check_status(Operation::Verify, status)
     }
 
     /// Verify a message with a context string
@@ -502,13 +978,16 @@ impl Sig {
     ) -> Result<()> {
         let signature = signature.into();
         let pk = pk.into();
-        if signature.bytes.len() > self.length_signature()
-            || pk.bytes.len() != self.length_public_key()
-        {
-            return Err(Error::InvalidLength);
+        if signature.len() > self.length_signature() {
+            return Err(self.wrong_length(
+                Operation::Verify,
+                self.length_signature(),
+                signature.len(),
+            ));
         }
+        self.check_length(Operation::Verify, self.length_public_key(), pk.len())?;
         let sig = unsafe { self.sig.as_ref() };
-        let func = sig.verify_with_ctx_str.unwrap();
+        let func = sig.verify_with_ctx_str.ok_or(Error::Unsupported)?;
         // For algorithms without context string support, liboqs
         // expects the context to be NULL. Converting an empty
         // slice to a pointer doesn't actually do this.
@@ -528,6 +1007,888 @@ impl Sig {
                 pk.bytes.as_ptr(),
             )
         };
+        self.check_status(Operation::Verify, status)
+    }
+
+    /// Generate a new keypair into the provided buffers
+    ///
+    /// The key is written to the start of `pk` and `sk`, which must hold at least
+    /// [`length_public_key`](Sig::length_public_key) and
+    /// [`length_secret_key`](Sig::length_secret_key) bytes.
+    pub fn keypair_into(&self, pk: &mut [u8], sk: &mut [u8]) -> Result<()> {
+        self.check_capacity(Operation::Keypair, self.length_public_key(), pk.len())?;
+        self.check_capacity(Operation::Keypair, self.length_secret_key(), sk.len())?;
+        let sig = unsafe { self.sig.as_ref() };
+        let func = sig.keypair.ok_or(Error::Unsupported)?;
+        let status = unsafe { func(pk.as_mut_ptr(), sk.as_mut_ptr()) };
+        self.check_status(Operation::Keypair, status)
+    }
+
+    /// Sign a message into the provided buffer, returning the length of the signature
+    ///
+    /// The signature is written to the start of `signature`, which must hold at least
+    /// [`length_signature`](Sig::length_signature) bytes.
+    pub fn sign_into<'a, S: Into<SecretKeyRef<'a>>>(
+        &self,
+        message: &Message,
+        sk: S,
+        signature: &mut [u8],
+    ) -> Result<usize> {
+        self.sign_to(message, None, sk.into().bytes, signature)
+    }
+
+    /// Sign a message with a context string into the provided buffer,
+    /// returning the length of the signature
+    ///
+    /// The signature is written to the start of `signature`, which must hold at least
+    /// [`length_signature`](Sig::length_signature) bytes.
+    pub fn sign_with_ctx_str_into<'a, S: Into<SecretKeyRef<'a>>>(
+        &self,
+        message: &Message,
+        ctx_str: &CtxStr,
+        sk: S,
+        signature: &mut [u8],
+    ) -> Result<usize> {
+        self.sign_to(message, Some(ctx_str), sk.into().bytes, signature)
+    }
+
+    /// Sign `message` into the provided buffer, returning the length of the signature
+    fn sign_to(
+        &self,
+        message: &Message,
+        ctx_str: Option<&CtxStr>,
+        sk: &[u8],
+        signature: &mut [u8],
+    ) -> Result<usize> {
+        self.check_length(Operation::Sign, self.length_secret_key(), sk.len())?;
+        self.check_capacity(Operation::Sign, self.length_signature(), signature.len())?;
+        let sig = unsafe { self.sig.as_ref() };
+        let mut sig_len = 0;
+        let status = match ctx_str {
+            None => {
+                let func = sig.sign.ok_or(Error::Unsupported)?;
+                unsafe {
+                    func(
+                        signature.as_mut_ptr(),
+                        &mut sig_len,
+                        message.as_ptr(),
+                        message.len(),
+                        sk.as_ptr(),
+                    )
+                }
+            }
+            Some(ctx_str) => {
+                let func = sig.sign_with_ctx_str.ok_or(Error::Unsupported)?;
+                // liboqs expects the context to be NULL if it is empty
+                let ctx_str_ptr = if !ctx_str.is_empty() {
+                    ctx_str.as_ptr()
+                } else {
+                    null()
+                };
+                unsafe {
+                    func(
+                        signature.as_mut_ptr(),
+                        &mut sig_len,
+                        message.as_ptr(),
+                        message.len(),
+                        ctx_str_ptr,
+                        ctx_str.len(),
+                        sk.as_ptr(),
+                    )
+                }
+            }
+        };
+        self.check_status(Operation::Sign, status)?;
+        Ok(sig_len)
+    }
+
+    /// Fails with [`Error::WrongLength`] if an input of `operation` is not `expected` bytes long
+    fn check_length(&self, operation: Operation, expected: usize, actual: usize) -> Result<()> {
+        if actual != expected {
+            return Err(self.wrong_length(operation, expected, actual));
+        }
+        Ok(())
+    }
+
+    /// Fails with [`Error::WrongLength`] if an output buffer of `operation` is too short
+    fn check_capacity(&self, operation: Operation, expected: usize, actual: usize) -> Result<()> {
+        if actual < expected {
+            return Err(self.wrong_length(operation, expected, actual));
+        }
+        Ok(())
+    }
+
+    fn wrong_length(&self, operation: Operation, expected: usize, actual: usize) -> Error {
+        Error::WrongLength {
+            operation,
+            algorithm: self.algorithm.name(),
+            expected,
+            actual,
+        }
+    }
+
+    /// Convert the status returned by liboqs for `operation`
+    fn check_status(&self, operation: Operation, status: OQS_STATUS) -> Result<()> {
         status_to_result(status)
+            .map_err(|error| error.in_operation(operation, self.algorithm.name()))
+    }
+
+    /// Start signing a message that is passed in pieces
+    ///
+    /// See [`StreamingSigner`].
+    pub fn signer<'a>(&'a self, sk: impl Into<SecretKeyRef<'a>>) -> StreamingSigner<'a> {
+        StreamingSigner {
+            sig: self,
+            sk: sk.into(),
+            ctx_str: None,
+            message: Vec::new(),
+        }
+    }
+
+    /// Start signing a message that is passed in pieces, with a context string
+    pub fn signer_with_ctx_str<'a>(
+        &'a self,
+        ctx_str: &'a CtxStr,
+        sk: impl Into<SecretKeyRef<'a>>,
+    ) -> StreamingSigner<'a> {
+        StreamingSigner {
+            ctx_str: Some(ctx_str),
+            ..self.signer(sk)
+        }
+    }
+
+    /// Start verifying a signature of a message that is passed in pieces
+    ///
+    /// See [`StreamingVerifier`].
+    pub fn verifier<'a>(
+        &'a self,
+        pk: impl Into<PublicKeyRef<'a>>,
+        signature: impl Into<SignatureRef<'a>>,
+    ) -> StreamingVerifier<'a> {
+        StreamingVerifier {
+            sig: self,
+            pk: pk.into(),
+            signature: signature.into(),
+            ctx_str: None,
+            message: Vec::new(),
+        }
+    }
+
+    /// Start verifying a signature with a context string of a message that is passed in pieces
+    pub fn verifier_with_ctx_str<'a>(
+        &'a self,
+        ctx_str: &'a CtxStr,
+        pk: impl Into<PublicKeyRef<'a>>,
+        signature: impl Into<SignatureRef<'a>>,
+    ) -> StreamingVerifier<'a> {
+        StreamingVerifier {
+            ctx_str: Some(ctx_str),
+            ..self.verifier(pk, signature)
+        }
+    }
+}
+
+/// Signs a message that is passed in pieces
+///
+/// The signatures of liboqs are computed over the whole message, so the pieces
+/// are collected until [`finalize`](StreamingSigner::finalize) signs them with
+/// [`Sig::sign`] or [`Sig::sign_with_ctx_str`].
+/// Under the `std` feature this implements [`std::io::Write`].
+///
+/// # Example
+/// ```rust
+/// # if !cfg!(feature = "ml_dsa") { return; }
+/// use oqs::sig::{Algorithm, Sig};
+/// oqs::init();
+/// let scheme = Sig::new(Algorithm::MlDsa44).unwrap();
+/// let (pk, sk) = scheme.keypair().unwrap();
+/// let mut signer = scheme.signer(&sk);
+/// signer.update(b"hello ");
+/// signer.update(b"world");
+/// let signature = signer.finalize().unwrap();
+/// assert!(scheme.verify(b"hello world", &signature, &pk).is_ok());
+/// ```
+pub struct StreamingSigner<'a> {
+    sig: &'a Sig,
+    sk: SecretKeyRef<'a>,
+    ctx_str: Option<&'a CtxStr>,
+    message: Vec<u8>,
+}
+
+impl StreamingSigner<'_> {
+    /// Append `data` to the message
+    pub fn update(&mut self, data: &[u8]) {
+        self.message.extend_from_slice(data);
+    }
+
+    /// Sign the message
+    pub fn finalize(self) -> Result<Signature> {
+        match self.ctx_str {
+            None => self.sig.sign(&self.message, self.sk),
+            Some(ctx_str) => self.sig.sign_with_ctx_str(&self.message, ctx_str, self.sk),
+        }
+    }
+}
+
+impl core::fmt::Debug for StreamingSigner<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StreamingSigner")
+            .field("algorithm", &self.sig.algorithm())
+            .finish_non_exhaustive()
     }
 }
+
+#[cfg(feature = "std")]
+impl std::io::Write for StreamingSigner<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Verifies a signature of a message that is passed in pieces
+///
+/// Like [`StreamingSigner`], this collects the pieces until
+/// [`finalize`](StreamingVerifier::finalize) verifies the whole message.
+/// Under the `std` feature this implements [`std::io::Write`].
+pub struct StreamingVerifier<'a> {
+    sig: &'a Sig,
+    pk: PublicKeyRef<'a>,
+    signature: SignatureRef<'a>,
+    ctx_str: Option<&'a CtxStr>,
+    message: Vec<u8>,
+}
+
+impl StreamingVerifier<'_> {
+    /// Append `data` to the message
+    pub fn update(&mut self, data: &[u8]) {
+        self.message.extend_from_slice(data);
+    }
+
+    /// Verify the signature of the message
+    pub fn finalize(self) -> Result<()> {
+        match self.ctx_str {
+            None => self.sig.verify(&self.message, self.signature, self.pk),
+            Some(ctx_str) => {
+                self.sig
+                    .verify_with_ctx_str(&self.message, self.signature, ctx_str, self.pk)
+            }
+        }
+    }
+}
+
+impl core::fmt::Debug for StreamingVerifier<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StreamingVerifier")
+            .field("algorithm", &self.sig.algorithm())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for StreamingVerifier<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Encoding of keys as DER and PEM documents
+#[cfg(feature = "pkcs8")]
+mod pkcs8_encoding {
+    use alloc::string::String;
+
+    use pkcs8::der::zeroize::Zeroizing;
+    use pkcs8::{Document, LineEnding, SecretDocument};
+
+    use super::*;
+    use crate::encoding::{self, DecodedSecretKey, PrivateKeyFormat};
+
+    impl Algorithm {
+        /// Layout of the secret key in PKCS#8 documents
+        fn private_key_format(self) -> PrivateKeyFormat {
+            match self {
+                Algorithm::MlDsa44 | Algorithm::MlDsa65 | Algorithm::MlDsa87 => {
+                    PrivateKeyFormat::Ietf
+                }
+                _ => PrivateKeyFormat::OqsProvider,
+            }
+        }
+    }
+
+    /// Encoding of keys as DER and PEM documents
+    ///
+    /// Public keys are encoded as `SubjectPublicKeyInfo` and secret keys as PKCS#8,
+    /// identified by [`Algorithm::oid`]. For ML-DSA, the secret key uses the `expandedKey`
+    /// format of the IETF LAMPS draft. Other algorithms store the secret key followed by
+    /// the public key, like oqs-provider.
+    ///
+    /// Algorithms without an OID fail with [`Error::Unsupported`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # if !cfg!(feature = "ml_dsa") { return; }
+    /// use oqs::sig::{Algorithm, Sig};
+    /// oqs::init();
+    /// let sig = Sig::new(Algorithm::MlDsa65).unwrap();
+    /// let (pk, sk) = sig.keypair().unwrap();
+    /// let pem = sig.public_key_to_pem(&pk, pkcs8::LineEnding::LF).unwrap();
+    /// assert_eq!(sig.public_key_from_pem(&pem).unwrap(), pk);
+    /// let der = sig.secret_key_to_der(&sk, &pk).unwrap();
+    /// assert_eq!(sig.secret_key_from_der(der.as_bytes()).unwrap(), sk);
+    /// ```
+    impl Sig {
+        fn oid(&self) -> Result<ObjectIdentifier> {
+            self.algorithm.oid().ok_or(Error::Unsupported)
+        }
+
+        fn lengths(&self) -> (usize, usize) {
+            (self.length_secret_key(), self.length_public_key())
+        }
+
+        fn check_keys(&self, sk: &[u8], pk: &[u8]) -> Result<()> {
+            self.check_length(Operation::Encode, self.length_secret_key(), sk.len())?;
+            self.check_length(Operation::Encode, self.length_public_key(), pk.len())
+        }
+
+        fn decoded_secret_key(&self, decoded: DecodedSecretKey<'_>) -> Result<SecretKey> {
+            match decoded {
+                DecodedSecretKey::Expanded(sk) => Ok(SecretKeyRef::new(sk).to_owned()),
+                // liboqs cannot derive signature keys from a seed
+                DecodedSecretKey::Seed(_) => Err(Error::Unsupported),
+            }
+        }
+
+        /// Encode a public key as DER `SubjectPublicKeyInfo`
+        pub fn public_key_to_der<'a>(&self, pk: impl Into<PublicKeyRef<'a>>) -> Result<Document> {
+            let pk = pk.into();
+            self.check_length(Operation::Encode, self.length_public_key(), pk.len())?;
+            encoding::public_key_to_der(self.oid()?, &pk)
+        }
+
+        /// Encode a public key as PEM `SubjectPublicKeyInfo`
+        pub fn public_key_to_pem<'a>(
+            &self,
+            pk: impl Into<PublicKeyRef<'a>>,
+            line_ending: LineEnding,
+        ) -> Result<String> {
+            let pk = pk.into();
+            self.check_length(Operation::Encode, self.length_public_key(), pk.len())?;
+            encoding::public_key_to_pem(self.oid()?, &pk, line_ending)
+        }
+
+        /// Decode a public key from DER `SubjectPublicKeyInfo`
+        ///
+        /// Fails with [`Error::AlgorithmMismatch`] if the key belongs to another algorithm.
+        pub fn public_key_from_der(&self, der: &[u8]) -> Result<PublicKey> {
+            let bytes = encoding::public_key_from_der(
+                self.oid()?,
+                self.algorithm.name(),
+                der,
+                self.length_public_key(),
+            )?;
+            Ok(PublicKey { bytes })
+        }
+
+        /// Decode a public key from PEM `SubjectPublicKeyInfo`
+        ///
+        /// Fails with [`Error::AlgorithmMismatch`] if the key belongs to another algorithm.
+        pub fn public_key_from_pem(&self, pem: &str) -> Result<PublicKey> {
+            let bytes = encoding::public_key_from_pem(
+                self.oid()?,
+                self.algorithm.name(),
+                pem,
+                self.length_public_key(),
+            )?;
+            Ok(PublicKey { bytes })
+        }
+
+        /// Encode a secret key as DER PKCS#8
+        pub fn secret_key_to_der<'a, 'b>(
+            &self,
+            sk: impl Into<SecretKeyRef<'a>>,
+            pk: impl Into<PublicKeyRef<'b>>,
+        ) -> Result<SecretDocument> {
+            let (sk, pk) = (sk.into(), pk.into());
+            self.check_keys(&sk, &pk)?;
+            let format = self.algorithm.private_key_format();
+            encoding::secret_key_to_der(self.oid()?, format, &sk, &pk)
+        }
+
+        /// Encode a secret key as PEM PKCS#8
+        pub fn secret_key_to_pem<'a, 'b>(
+            &self,
+            sk: impl Into<SecretKeyRef<'a>>,
+            pk: impl Into<PublicKeyRef<'b>>,
+            line_ending: LineEnding,
+        ) -> Result<Zeroizing<String>> {
+            let (sk, pk) = (sk.into(), pk.into());
+            self.check_keys(&sk, &pk)?;
+            let format = self.algorithm.private_key_format();
+            encoding::secret_key_to_pem(self.oid()?, format, &sk, &pk, line_ending)
+        }
+
+        /// Decode a secret key from DER PKCS#8
+        ///
+        /// Fails with [`Error::AlgorithmMismatch`] if the key belongs to another algorithm.
+        pub fn secret_key_from_der(&self, der: &[u8]) -> Result<SecretKey> {
+            let format = self.algorithm.private_key_format();
+            encoding::secret_key_from_der(
+                self.oid()?,
+                self.algorithm.name(),
+                format,
+                der,
+                self.lengths(),
+                |decoded| self.decoded_secret_key(decoded),
+            )
+        }
+
+        /// Decode a secret key from PEM PKCS#8
+        ///
+        /// Fails with [`Error::AlgorithmMismatch`] if the key belongs to another algorithm.
+        pub fn secret_key_from_pem(&self, pem: &str) -> Result<SecretKey> {
+            let format = self.algorithm.private_key_format();
+            encoding::secret_key_from_pem(
+                self.oid()?,
+                self.algorithm.name(),
+                format,
+                pem,
+                self.lengths(),
+                |decoded| self.decoded_secret_key(decoded),
+            )
+        }
+    }
+}
+
+/// Statically-typed signature API
+///
+/// Every algorithm has a marker type, like [`MlDsa65`](super::MlDsa65), that implements
+/// [`SigAlgorithm`]. The buffers in this module are generic over that marker type and
+/// have the sizes of the algorithm, so that mixing up keys of different algorithms
+/// is a compile error.
+///
+/// The typed buffers can also be passed to the methods of [`Sig`].
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "ml_dsa")] {
+/// use oqs::sig::{MlDsa65, SigAlgorithm};
+/// oqs::init();
+/// let message = [0u8; 100];
+/// let (pk, sk) = MlDsa65::keypair().unwrap();
+/// let signature = MlDsa65::sign(&message, &sk).unwrap();
+/// assert!(MlDsa65::verify(&message, &signature, &pk).is_ok());
+/// let array: &[u8; 1952] = pk.as_array();
+/// # }
+/// ```
+pub mod typed {
+    use alloc::boxed::Box;
+
+    use super::*;
+    use crate::newtype_array;
+
+    newtype_array!(PublicKey, SigAlgorithm::PublicKeyBytes);
+    newtype_array!(secret SecretKey, SigAlgorithm::SecretKeyBytes);
+
+    /// New owned signature
+    ///
+    /// The array has room for the longest signature of the algorithm `A`,
+    /// as signatures of some algorithms vary in length.
+    pub struct Signature<A: SigAlgorithm> {
+        bytes: Box<A::SignatureBytes>,
+        len: usize,
+    }
+
+    impl<A: SigAlgorithm> Signature<A> {
+        /// Maximum length in bytes
+        pub const MAX_LENGTH: usize = <A::SignatureBytes as ByteArray>::LENGTH;
+
+        /// Construct a zeroed signature of maximum length, to be filled by liboqs
+        fn zeroed() -> Self {
+            Signature {
+                bytes: A::SignatureBytes::boxed_zeroed(),
+                len: Self::MAX_LENGTH,
+            }
+        }
+
+        /// Construct a signature from bytes
+        ///
+        /// Fails with [`Error::WrongLength`] if `bytes` is longer than [`Self::MAX_LENGTH`].
+        pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+            if bytes.len() > Self::MAX_LENGTH {
+                return Err(Error::WrongLength {
+                    operation: Operation::Decode,
+                    algorithm: A::ALGORITHM.name(),
+                    expected: Self::MAX_LENGTH,
+                    actual: bytes.len(),
+                });
+            }
+            let mut signature = Self::zeroed();
+            signature.bytes.as_mut_slice()[..bytes.len()].copy_from_slice(bytes);
+            signature.len = bytes.len();
+            Ok(signature)
+        }
+
+        /// Get the contained array
+        ///
+        /// Only the first [`len`](Self::len) bytes are part of the signature,
+        /// the rest are zeroes.
+        pub fn as_array(&self) -> &A::SignatureBytes {
+            &self.bytes
+        }
+
+        /// Length in bytes
+        #[allow(clippy::len_without_is_empty)]
+        pub fn len(&self) -> usize {
+            self.len
+        }
+    }
+
+    impl<A: SigAlgorithm> Clone for Signature<A> {
+        fn clone(&self) -> Self {
+            // Cloning the box itself would copy the array through the stack
+            Self::from_bytes(self.as_ref()).expect("the signature fits")
+        }
+    }
+
+    impl<A: SigAlgorithm> core::fmt::Debug for Signature<A> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("Signature")
+                .field("algorithm", &A::ALGORITHM)
+                .field("bytes", &self.as_ref())
+                .finish()
+        }
+    }
+
+    impl<A: SigAlgorithm> PartialEq for Signature<A> {
+        fn eq(&self, other: &Self) -> bool {
+            self.as_ref() == other.as_ref()
+        }
+    }
+
+    impl<A: SigAlgorithm> Eq for Signature<A> {}
+
+    impl<A: SigAlgorithm> AsRef<[u8]> for Signature<A> {
+        fn as_ref(&self) -> &[u8] {
+            &self.bytes.as_slice()[..self.len]
+        }
+    }
+
+    impl<'a, A: SigAlgorithm> TryFrom<&'a [u8]> for Signature<A> {
+        type Error = Error;
+
+        fn try_from(bytes: &'a [u8]) -> Result<Self> {
+            Self::from_bytes(bytes)
+        }
+    }
+
+    /// Signature scheme that is known at compile time
+    ///
+    /// The associated types are the `[u8; N]` arrays that hold the buffers of the algorithm.
+    pub trait SigAlgorithm:
+        Clone + Copy + core::fmt::Debug + Default + Eq + Send + Sync + 'static
+    {
+        /// The algorithm
+        const ALGORITHM: Algorithm;
+        /// Array holding a public key
+        type PublicKeyBytes: ByteArray;
+        /// Array holding a secret key
+        type SecretKeyBytes: ByteArray;
+        /// Array holding a signature of maximum length
+        type SignatureBytes: ByteArray;
+
+        /// Generate a new keypair
+        fn keypair() -> Result<(PublicKey<Self>, SecretKey<Self>)> {
+            let sig = Sig::new(Self::ALGORITHM)?;
+            let mut pk = PublicKey::<Self>::zeroed();
+            let mut sk = SecretKey::<Self>::zeroed();
+            sig.keypair_into(pk.bytes.as_mut_slice(), sk.bytes.as_mut_slice())?;
+            Ok((pk, sk))
+        }
+
+        /// Sign a message
+        fn sign(message: &Message, sk: &SecretKey<Self>) -> Result<Signature<Self>> {
+            let sig = Sig::new(Self::ALGORITHM)?;
+            let mut signature = Signature::<Self>::zeroed();
+            signature.len = sig.sign_into(message, sk, signature.bytes.as_mut_slice())?;
+            Ok(signature)
+        }
+
+        /// Sign a message with a context string
+        fn sign_with_ctx_str(
+            message: &Message,
+            ctx_str: &CtxStr,
+            sk: &SecretKey<Self>,
+        ) -> Result<Signature<Self>> {
+            let sig = Sig::new(Self::ALGORITHM)?;
+            let mut signature = Signature::<Self>::zeroed();
+            signature.len =
+                sig.sign_with_ctx_str_into(message, ctx_str, sk, signature.bytes.as_mut_slice())?;
+            Ok(signature)
+        }
+
+        /// Verify a message
+        fn verify(
+            message: &Message,
+            signature: &Signature<Self>,
+            pk: &PublicKey<Self>,
+        ) -> Result<()> {
+            Sig::new(Self::ALGORITHM)?.verify(message, signature, pk)
+        }
+
+        /// Verify a message with a context string
+        fn verify_with_ctx_str(
+            message: &Message,
+            signature: &Signature<Self>,
+            ctx_str: &CtxStr,
+            pk: &PublicKey<Self>,
+        ) -> Result<()> {
+            Sig::new(Self::ALGORITHM)?.verify_with_ctx_str(message, signature, ctx_str, pk)
+        }
+    }
+
+    impl<'a, A: SigAlgorithm> From<&'a PublicKey<A>> for PublicKeyRef<'a> {
+        fn from(pk: &'a PublicKey<A>) -> PublicKeyRef<'a> {
+            PublicKeyRef::new(pk.as_ref())
+        }
+    }
+
+    impl<'a, A: SigAlgorithm> From<&'a SecretKey<A>> for SecretKeyRef<'a> {
+        fn from(sk: &'a SecretKey<A>) -> SecretKeyRef<'a> {
+            SecretKeyRef::new(sk.as_ref())
+        }
+    }
+
+    impl<'a, A: SigAlgorithm> From<&'a Signature<A>> for SignatureRef<'a> {
+        fn from(signature: &'a Signature<A>) -> SignatureRef<'a> {
+            SignatureRef::new(signature.as_ref())
+        }
+    }
+}
+
+pub use typed::SigAlgorithm;
+
+/// Adapters for the RustCrypto [`signature`](::signature) traits
+///
+/// [`SigningKey`] and [`VerifyingKey`] own their [`Sig`] handle and key,
+/// so they can be used wherever code is generic over
+/// [`Signer`](::signature::Signer), [`Verifier`](::signature::Verifier)
+/// and [`Keypair`](::signature::Keypair).
+#[cfg(feature = "signature")]
+mod signature_traits {
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn to_signature_error(error: Error) -> ::signature::Error {
+        #[cfg(feature = "std")]
+        return ::signature::Error::from_source(error);
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = error;
+            ::signature::Error::new()
+        }
+    }
+
+    /// Signing key implementing [`Signer`](::signature::Signer) and
+    /// [`Keypair`](::signature::Keypair)
+    ///
+    /// # Example
+    /// ```rust
+    /// # if !cfg!(feature = "ml_dsa") { return; }
+    /// use oqs::sig::{Algorithm, SigningKey};
+    /// use signature::{Keypair, Signer, Verifier};
+    /// oqs::init();
+    /// let signing_key = SigningKey::generate(Algorithm::MlDsa65).unwrap();
+    /// let signature = signing_key.sign(b"message");
+    /// assert!(signing_key.verifying_key().verify(b"message", &signature).is_ok());
+    /// ```
+    pub struct SigningKey {
+        sk: SecretKey,
+        verifying_key: VerifyingKey,
+    }
+
+    /// Verifying key implementing [`Verifier`](::signature::Verifier)
+    #[derive(Clone)]
+    pub struct VerifyingKey {
+        sig: Arc<Sig>,
+        pk: PublicKey,
+    }
+
+    impl SigningKey {
+        /// Generate a new keypair for `algorithm`
+        pub fn generate(algorithm: Algorithm) -> Result<Self> {
+            let sig = Sig::new(algorithm)?;
+            let (pk, sk) = sig.keypair()?;
+            Self::new(sig, pk, sk)
+        }
+
+        /// Construct a signing key from an existing keypair
+        ///
+        /// Fails with [`Error::WrongLength`] if the keys do not fit the algorithm of `sig`.
+        pub fn new(sig: Sig, pk: PublicKey, sk: SecretKey) -> Result<Self> {
+            sig.check_length(Operation::Sign, sig.length_secret_key(), sk.len())?;
+            Ok(Self {
+                sk,
+                verifying_key: VerifyingKey::new(sig, pk)?,
+            })
+        }
+
+        /// Get the algorithm of this key
+        pub fn algorithm(&self) -> Algorithm {
+            self.verifying_key.algorithm()
+        }
+
+        /// Get the secret key
+        pub fn secret_key(&self) -> SecretKeyRef<'_> {
+            SecretKeyRef::from(&self.sk)
+        }
+
+        /// Get the public key
+        pub fn public_key(&self) -> PublicKeyRef<'_> {
+            self.verifying_key.public_key()
+        }
+    }
+
+    impl VerifyingKey {
+        /// Construct a verifying key from a public key
+        ///
+        /// Fails with [`Error::WrongLength`] if the key does not fit the algorithm of `sig`.
+        pub fn new(sig: Sig, pk: PublicKey) -> Result<Self> {
+            sig.check_length(Operation::Verify, sig.length_public_key(), pk.len())?;
+            Ok(Self {
+                sig: Arc::new(sig),
+                pk,
+            })
+        }
+
+        /// Construct a verifying key from the bytes of a public key for `algorithm`
+        pub fn from_bytes(algorithm: Algorithm, pk: &[u8]) -> Result<Self> {
+            let sig = Sig::new(algorithm)?;
+            let pk = PublicKeyRef::new(pk).to_owned();
+            Self::new(sig, pk)
+        }
+
+        /// Get the algorithm of this key
+        pub fn algorithm(&self) -> Algorithm {
+            self.sig.algorithm()
+        }
+
+        /// Get the public key
+        pub fn public_key(&self) -> PublicKeyRef<'_> {
+            PublicKeyRef::from(&self.pk)
+        }
+    }
+
+    impl core::fmt::Debug for SigningKey {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("SigningKey")
+                .field("algorithm", &self.algorithm())
+                .field("sk", &self.sk)
+                .finish()
+        }
+    }
+
+    impl core::fmt::Debug for VerifyingKey {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("VerifyingKey")
+                .field("algorithm", &self.algorithm())
+                .field("pk", &self.pk)
+                .finish()
+        }
+    }
+
+    impl PartialEq for VerifyingKey {
+        fn eq(&self, other: &Self) -> bool {
+            self.algorithm() == other.algorithm() && self.pk == other.pk
+        }
+    }
+
+    impl Eq for VerifyingKey {}
+
+    impl ::signature::Signer<Signature> for SigningKey {
+        fn try_sign(&self, msg: &[u8]) -> core::result::Result<Signature, ::signature::Error> {
+            self.verifying_key
+                .sig
+                .sign(msg, &self.sk)
+                .map_err(to_signature_error)
+        }
+    }
+
+    impl ::signature::Keypair for SigningKey {
+        type VerifyingKey = VerifyingKey;
+
+        fn verifying_key(&self) -> VerifyingKey {
+            self.verifying_key.clone()
+        }
+    }
+
+    impl AsRef<VerifyingKey> for SigningKey {
+        fn as_ref(&self) -> &VerifyingKey {
+            &self.verifying_key
+        }
+    }
+
+    impl ::signature::Verifier<Signature> for VerifyingKey {
+        fn verify(
+            &self,
+            msg: &[u8],
+            signature: &Signature,
+        ) -> core::result::Result<(), ::signature::Error> {
+            self.sig
+                .verify(msg, signature, &self.pk)
+                .map_err(to_signature_error)
+        }
+    }
+
+    impl ::signature::Verifier<Signature> for SigningKey {
+        fn verify(
+            &self,
+            msg: &[u8],
+            signature: &Signature,
+        ) -> core::result::Result<(), ::signature::Error> {
+            ::signature::Verifier::verify(&self.verifying_key, msg, signature)
+        }
+    }
+
+    impl<'a> TryFrom<&'a [u8]> for Signature {
+        type Error = ::signature::Error;
+
+        /// Signatures are checked against the length of the algorithm when verifying
+        fn try_from(bytes: &'a [u8]) -> core::result::Result<Self, Self::Error> {
+            Ok(Signature {
+                bytes: bytes.to_vec(),
+            })
+        }
+    }
+
+    impl From<Signature> for Vec<u8> {
+        fn from(signature: Signature) -> Vec<u8> {
+            signature.into_vec()
+        }
+    }
+
+    impl ::signature::SignatureEncoding for Signature {
+        type Repr = Vec<u8>;
+
+        fn to_vec(&self) -> Vec<u8> {
+            self.bytes.clone()
+        }
+
+        fn encoded_len(&self) -> usize {
+            self.len()
+        }
+    }
+}
+
+#[cfg(feature = "signature")]
+pub use signature_traits::{SigningKey, VerifyingKey};
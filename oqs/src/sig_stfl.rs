@@ -0,0 +1,747 @@
+//! Stateful signature API
+//!
+//! Stateful hash-based signature schemes (XMSS, XMSS<sup>MT</sup>, LMS and HSS)
+//! change their secret key with every signature that is produced.
+//! Signing twice with the same state breaks the security of these schemes,
+//! so every [`SecretKey`] carries a callback that has to persist the updated
+//! key before a signature is handed out.
+//!
+//! See [`StatefulSig`] for the main functionality and [`Algorithm`]
+//! for the list of supported algorithms.
+//!
+//! liboqs only supports verification by default: key generation and
+//! signing require the `stateful_key_sig_gen` feature.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use core::ptr::{null_mut, NonNull};
+
+#[cfg(not(feature = "std"))]
+use cstr_core::CStr;
+#[cfg(feature = "std")]
+use std::ffi::CStr;
+
+use crate::ffi::sig_stfl as ffi;
+use crate::newtype_buffer;
+use crate::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+newtype_buffer!(PublicKey, PublicKeyRef);
+newtype_buffer!(Signature, SignatureRef);
+newtype_buffer!(secret SecretKeyBytes, SecretKeyBytesRef);
+
+/// Message type
+pub type Message = [u8];
+
+/// Callback that persists the serialized secret key
+///
+/// Returning an error from this callback makes signing fail.
+type StoreCallback = dyn FnMut(&[u8]) -> Result<()> + Send;
+
+/// Family of a stateful signature algorithm
+///
+/// Optional support for `serde` if that feature is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Family {
+    /// LMS and HSS (RFC 8554)
+    Lms,
+    /// XMSS and XMSS<sup>MT</sup> (RFC 8391)
+    Xmss,
+}
+
+/// The family of the algorithms that the crate feature `feature` enables
+fn feature_family(feature: &str) -> Family {
+    match feature {
+        "lms" => Family::Lms,
+        _ => Family::Xmss,
+    }
+}
+
+macro_rules! implement_sig_stfls {
+    { $(($feat: literal) $sig: ident: $oqs_id: ident),* $(,)? } => (
+        /// Supported stateful algorithms by liboqs
+        ///
+        /// They may not all be enabled
+        ///
+        /// Optional support for `serde` if that feature is enabled.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        #[allow(missing_docs)]
+        pub enum Algorithm {
+            $(
+                $sig,
+            )*
+        }
+
+        fn algorithm_to_id(algorithm: Algorithm) -> *const libc::c_char {
+            let id: &[u8] = match algorithm {
+                $(
+                    Algorithm::$sig => &ffi::$oqs_id[..],
+                )*
+            };
+            id as *const _ as *const libc::c_char
+        }
+
+        impl Algorithm {
+            /// Returns the family of the algorithm
+            pub fn family(self) -> Family {
+                match self {
+                    $(
+                        Algorithm::$sig => feature_family($feat),
+                    )*
+                }
+            }
+        }
+
+        $(
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod $sig {
+                use super::*;
+
+                #[test]
+                fn test_enabled() {
+                    crate::init();
+                    if cfg!(feature = $feat) {
+                        assert!(Algorithm::$sig.is_enabled());
+                    } else {
+                        assert!(!Algorithm::$sig.is_enabled())
+                    }
+                }
+
+                #[test]
+                fn test_name() {
+                    let algo = Algorithm::$sig;
+                    // Just make sure the name impl does not panic or crash.
+                    let name = algo.name();
+
+                    #[cfg(feature = "std")]
+                    assert_eq!(name, algo.to_string());
+
+                    // ... And actually contains something.
+                    assert!(!name.is_empty());
+                }
+
+                #[test]
+                fn test_family() {
+                    crate::init();
+                    let algorithm = Algorithm::$sig;
+                    if algorithm.is_enabled() {
+                        let families = crate::build_info().stateful_sig_families;
+                        assert!(families.contains(&algorithm.family()));
+                    }
+                }
+
+                #[test]
+                fn test_get_algorithm_back() {
+                    let algorithm = Algorithm::$sig;
+                    if algorithm.is_enabled() {
+                        let sig = StatefulSig::new(algorithm).unwrap();
+                        assert_eq!(algorithm, sig.algorithm());
+                    }
+                }
+
+                #[test]
+                fn test_version() {
+                    if let Ok(sig) = StatefulSig::new(Algorithm::$sig) {
+                        // Just make sure the version can be called without panic
+                        let version = sig.version();
+                        // ... And actually contains something.
+                        assert!(!version.is_empty());
+                    }
+                }
+            }
+        )*
+    )
+}
+
+implement_sig_stfls! {
+    ("xmss") XmssSha256H10: OQS_SIG_STFL_alg_xmss_sha256_h10,
+    ("xmss") XmssSha256H16: OQS_SIG_STFL_alg_xmss_sha256_h16,
+    ("xmss") XmssSha256H20: OQS_SIG_STFL_alg_xmss_sha256_h20,
+    ("xmss") XmssSha512H10: OQS_SIG_STFL_alg_xmss_sha512_h10,
+    ("xmss") XmssSha512H16: OQS_SIG_STFL_alg_xmss_sha512_h16,
+    ("xmss") XmssSha512H20: OQS_SIG_STFL_alg_xmss_sha512_h20,
+    ("xmss") XmssShake128H10: OQS_SIG_STFL_alg_xmss_shake128_h10,
+    ("xmss") XmssShake128H16: OQS_SIG_STFL_alg_xmss_shake128_h16,
+    ("xmss") XmssShake128H20: OQS_SIG_STFL_alg_xmss_shake128_h20,
+    ("xmss") XmssShake256H10: OQS_SIG_STFL_alg_xmss_shake256_h10,
+    ("xmss") XmssShake256H16: OQS_SIG_STFL_alg_xmss_shake256_h16,
+    ("xmss") XmssShake256H20: OQS_SIG_STFL_alg_xmss_shake256_h20,
+    ("xmss") XmssMtSha256H20_2: OQS_SIG_STFL_alg_xmssmt_sha256_h20_2,
+    ("xmss") XmssMtSha256H20_4: OQS_SIG_STFL_alg_xmssmt_sha256_h20_4,
+    ("xmss") XmssMtSha256H40_2: OQS_SIG_STFL_alg_xmssmt_sha256_h40_2,
+    ("xmss") XmssMtSha256H40_4: OQS_SIG_STFL_alg_xmssmt_sha256_h40_4,
+    ("xmss") XmssMtSha256H40_8: OQS_SIG_STFL_alg_xmssmt_sha256_h40_8,
+    ("xmss") XmssMtSha256H60_3: OQS_SIG_STFL_alg_xmssmt_sha256_h60_3,
+    ("xmss") XmssMtSha256H60_6: OQS_SIG_STFL_alg_xmssmt_sha256_h60_6,
+    ("xmss") XmssMtSha256H60_12: OQS_SIG_STFL_alg_xmssmt_sha256_h60_12,
+    ("xmss") XmssMtShake128H20_2: OQS_SIG_STFL_alg_xmssmt_shake128_h20_2,
+    ("xmss") XmssMtShake128H20_4: OQS_SIG_STFL_alg_xmssmt_shake128_h20_4,
+    ("xmss") XmssMtShake128H40_2: OQS_SIG_STFL_alg_xmssmt_shake128_h40_2,
+    ("xmss") XmssMtShake128H40_4: OQS_SIG_STFL_alg_xmssmt_shake128_h40_4,
+    ("xmss") XmssMtShake128H40_8: OQS_SIG_STFL_alg_xmssmt_shake128_h40_8,
+    ("xmss") XmssMtShake128H60_3: OQS_SIG_STFL_alg_xmssmt_shake128_h60_3,
+    ("xmss") XmssMtShake128H60_6: OQS_SIG_STFL_alg_xmssmt_shake128_h60_6,
+    ("xmss") XmssMtShake128H60_12: OQS_SIG_STFL_alg_xmssmt_shake128_h60_12,
+    ("lms") LmsSha256H5W1: OQS_SIG_STFL_alg_lms_sha256_h5_w1,
+    ("lms") LmsSha256H5W2: OQS_SIG_STFL_alg_lms_sha256_h5_w2,
+    ("lms") LmsSha256H5W4: OQS_SIG_STFL_alg_lms_sha256_h5_w4,
+    ("lms") LmsSha256H5W8: OQS_SIG_STFL_alg_lms_sha256_h5_w8,
+    ("lms") LmsSha256H5W8H5W8: OQS_SIG_STFL_alg_lms_sha256_h5_w8_h5_w8,
+    ("lms") LmsSha256H10W1: OQS_SIG_STFL_alg_lms_sha256_h10_w1,
+    ("lms") LmsSha256H10W2: OQS_SIG_STFL_alg_lms_sha256_h10_w2,
+    ("lms") LmsSha256H10W2H10W2: OQS_SIG_STFL_alg_lms_sha256_h10_w2_h10_w2,
+    ("lms") LmsSha256H10W4: OQS_SIG_STFL_alg_lms_sha256_h10_w4,
+    ("lms") LmsSha256H10W4H5W8: OQS_SIG_STFL_alg_lms_sha256_h10_w4_h5_w8,
+    ("lms") LmsSha256H10W4H10W4: OQS_SIG_STFL_alg_lms_sha256_h10_w4_h10_w4,
+    ("lms") LmsSha256H10W8: OQS_SIG_STFL_alg_lms_sha256_h10_w8,
+    ("lms") LmsSha256H10W8H5W8: OQS_SIG_STFL_alg_lms_sha256_h10_w8_h5_w8,
+    ("lms") LmsSha256H10W8H10W8: OQS_SIG_STFL_alg_lms_sha256_h10_w8_h10_w8,
+    ("lms") LmsSha256H15W1: OQS_SIG_STFL_alg_lms_sha256_h15_w1,
+    ("lms") LmsSha256H15W2: OQS_SIG_STFL_alg_lms_sha256_h15_w2,
+    ("lms") LmsSha256H15W4: OQS_SIG_STFL_alg_lms_sha256_h15_w4,
+    ("lms") LmsSha256H15W8: OQS_SIG_STFL_alg_lms_sha256_h15_w8,
+    ("lms") LmsSha256H15W8H5W8: OQS_SIG_STFL_alg_lms_sha256_h15_w8_h5_w8,
+    ("lms") LmsSha256H15W8H10W8: OQS_SIG_STFL_alg_lms_sha256_h15_w8_h10_w8,
+    ("lms") LmsSha256H15W8H15W8: OQS_SIG_STFL_alg_lms_sha256_h15_w8_h15_w8,
+    ("lms") LmsSha256H20W1: OQS_SIG_STFL_alg_lms_sha256_h20_w1,
+    ("lms") LmsSha256H20W2: OQS_SIG_STFL_alg_lms_sha256_h20_w2,
+    ("lms") LmsSha256H20W4: OQS_SIG_STFL_alg_lms_sha256_h20_w4,
+    ("lms") LmsSha256H20W8: OQS_SIG_STFL_alg_lms_sha256_h20_w8,
+    ("lms") LmsSha256H20W8H5W8: OQS_SIG_STFL_alg_lms_sha256_h20_w8_h5_w8,
+    ("lms") LmsSha256H20W8H10W8: OQS_SIG_STFL_alg_lms_sha256_h20_w8_h10_w8,
+    ("lms") LmsSha256H20W8H15W8: OQS_SIG_STFL_alg_lms_sha256_h20_w8_h15_w8,
+    ("lms") LmsSha256H20W8H20W8: OQS_SIG_STFL_alg_lms_sha256_h20_w8_h20_w8,
+    ("lms") LmsSha256H25W1: OQS_SIG_STFL_alg_lms_sha256_h25_w1,
+    ("lms") LmsSha256H25W2: OQS_SIG_STFL_alg_lms_sha256_h25_w2,
+    ("lms") LmsSha256H25W4: OQS_SIG_STFL_alg_lms_sha256_h25_w4,
+    ("lms") LmsSha256H25W8: OQS_SIG_STFL_alg_lms_sha256_h25_w8,
+}
+
+impl Algorithm {
+    /// Returns true if this algorithm is enabled in the linked version
+    /// of liboqs
+    pub fn is_enabled(self) -> bool {
+        unsafe { ffi::OQS_SIG_STFL_alg_is_enabled(algorithm_to_id(self)) == 1 }
+    }
+
+    /// Provides a pointer to the id of the algorithm
+    ///
+    /// For use with the FFI api methods
+    pub fn to_id(self) -> *const libc::c_char {
+        algorithm_to_id(self)
+    }
+
+    /// Returns the algorithm's name as a static Rust string.
+    ///
+    /// This is the same as the `to_id`, but as a safe Rust string.
+    pub fn name(&self) -> &'static str {
+        // SAFETY: The id from ffi must be a proper null terminated C string
+        let id = unsafe { CStr::from_ptr(self.to_id()) };
+        id.to_str().expect("OQS algorithm names must be UTF-8")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+/// Called by liboqs whenever the secret key has changed
+///
+/// `context` points to the `Box<StoreCallback>` owned by the [`SecretKey`].
+unsafe extern "C" fn store_secret_key(
+    sk_buf: *mut u8,
+    buf_len: usize,
+    context: *mut libc::c_void,
+) -> OQS_STATUS {
+    if sk_buf.is_null() || context.is_null() {
+        return OQS_STATUS::OQS_ERROR;
+    }
+    let store = &mut *(context as *mut Box<StoreCallback>);
+    let buf = core::slice::from_raw_parts(sk_buf, buf_len);
+    match store(buf) {
+        Ok(()) => OQS_STATUS::OQS_SUCCESS,
+        Err(_) => OQS_STATUS::OQS_ERROR,
+    }
+}
+
+/// Secret key of a stateful signature scheme
+///
+/// The key is updated in place by [`StatefulSig::sign`],
+/// which only returns the signature after the `store` callback
+/// that was provided when creating or loading the key has persisted
+/// the new state.
+///
+/// The key material is freed securely when this is dropped.
+pub struct SecretKey {
+    algorithm: Algorithm,
+    sk: NonNull<ffi::OQS_SIG_STFL_SECRET_KEY>,
+    // Boxed twice so liboqs can hold a thin pointer to it
+    store: Box<Box<StoreCallback>>,
+}
+
+// The key is only modified through `&mut self`,
+// and the store callback is required to be `Send`.
+unsafe impl Sync for SecretKey {}
+unsafe impl Send for SecretKey {}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        unsafe { ffi::OQS_SIG_STFL_SECRET_KEY_free(self.sk.as_ptr()) };
+    }
+}
+
+impl core::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SecretKey")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecretKey {
+    /// Allocate an empty secret key with the store callback installed
+    fn new<F>(algorithm: Algorithm, store: F) -> Result<Self>
+    where
+        F: FnMut(&[u8]) -> Result<()> + Send + 'static,
+    {
+        let sk = unsafe { ffi::OQS_SIG_STFL_SECRET_KEY_new(algorithm_to_id(algorithm)) };
+        let sk = NonNull::new(sk).ok_or(Error::AlgorithmDisabled)?;
+        let mut sk = Self {
+            algorithm,
+            sk,
+            store: Box::new(Box::new(store)),
+        };
+        unsafe {
+            ffi::OQS_SIG_STFL_SECRET_KEY_SET_store_cb(
+                sk.sk.as_ptr(),
+                Some(store_secret_key),
+                sk.store_context(),
+            )
+        };
+        Ok(sk)
+    }
+
+    fn store_context(&mut self) -> *mut libc::c_void {
+        &mut *self.store as *mut Box<StoreCallback> as *mut libc::c_void
+    }
+
+    /// Get the algorithm this key belongs to
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Serialize the current state of the secret key
+    ///
+    /// This is the same format that is passed to the store callback.
+    pub fn to_bytes(&self) -> Result<SecretKeyBytes> {
+        let mut buf = null_mut();
+        let mut buf_len = 0;
+        let status = unsafe {
+            ffi::OQS_SIG_STFL_SECRET_KEY_serialize(&mut buf, &mut buf_len, self.sk.as_ptr())
+        };
+        status_to_result(status)?;
+        if buf.is_null() {
+            return Err(Error::Error);
+        }
+        let bytes = SecretKeyBytes {
+            bytes: unsafe { core::slice::from_raw_parts(buf, buf_len) }.to_vec(),
+        };
+        unsafe { crate::ffi::common::OQS_MEM_secure_free(buf as *mut libc::c_void, buf_len) };
+        Ok(bytes)
+    }
+
+    /// Pass the current state to the store callback
+    fn persist(&mut self) -> Result<()> {
+        let bytes = self.to_bytes()?;
+        (self.store)(bytes.as_ref())
+    }
+}
+
+/// Stateful signature scheme
+///
+/// # Example
+/// ```rust
+/// # if !cfg!(all(feature = "lms", feature = "stateful_key_sig_gen")) { return; }
+/// use oqs;
+/// oqs::init();
+/// let scheme = oqs::sig_stfl::StatefulSig::new(oqs::sig_stfl::Algorithm::LmsSha256H5W1).unwrap();
+/// let message = [0u8; 100];
+/// // Persist every new secret key state, e.g. to disk, before signatures are released
+/// let (pk, mut sk) = scheme.keypair(|_state: &[u8]| Ok(())).unwrap();
+/// let signature = scheme.sign(&message, &mut sk).unwrap();
+/// assert!(scheme.verify(&message, &signature, &pk).is_ok());
+/// ```
+pub struct StatefulSig {
+    algorithm: Algorithm,
+    sig: NonNull<ffi::OQS_SIG_STFL>,
+}
+
+unsafe impl Sync for StatefulSig {}
+unsafe impl Send for StatefulSig {}
+
+impl Drop for StatefulSig {
+    fn drop(&mut self) {
+        unsafe { ffi::OQS_SIG_STFL_free(self.sig.as_ptr()) };
+    }
+}
+
+impl core::convert::TryFrom<Algorithm> for StatefulSig {
+    type Error = crate::Error;
+    fn try_from(alg: Algorithm) -> Result<StatefulSig> {
+        StatefulSig::new(alg)
+    }
+}
+
+impl StatefulSig {
+    /// Construct a new algorithm
+    ///
+    /// May fail if the algorithm is not available
+    pub fn new(algorithm: Algorithm) -> Result<Self> {
+        let sig = unsafe { ffi::OQS_SIG_STFL_new(algorithm_to_id(algorithm)) };
+        NonNull::new(sig).map_or_else(
+            || Err(Error::AlgorithmDisabled),
+            |sig| Ok(Self { algorithm, sig }),
+        )
+    }
+
+    /// Get the algorithm used by this `StatefulSig`
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Get the version of the implementation
+    pub fn version(&self) -> &'static str {
+        let sig = unsafe { self.sig.as_ref() };
+        // SAFETY: The alg_version from ffi must be a proper null terminated C string
+        let cstr = unsafe { CStr::from_ptr(sig.alg_version) };
+        cstr.to_str()
+            .expect("Algorithm version strings must be UTF-8")
+    }
+
+    /// Is this algorithm EUF-CMA?
+    pub fn is_euf_cma(&self) -> bool {
+        let sig = unsafe { self.sig.as_ref() };
+        sig.euf_cma
+    }
+
+    /// Length of the public key
+    pub fn length_public_key(&self) -> usize {
+        let sig = unsafe { self.sig.as_ref() };
+        sig.length_public_key
+    }
+
+    /// Length of the secret key
+    pub fn length_secret_key(&self) -> usize {
+        let sig = unsafe { self.sig.as_ref() };
+        sig.length_secret_key
+    }
+
+    /// Maximum length of a signature
+    pub fn length_signature(&self) -> usize {
+        let sig = unsafe { self.sig.as_ref() };
+        sig.length_signature
+    }
+
+    /// Construct a public key object from bytes
+    pub fn public_key_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<PublicKeyRef<'a>> {
+        if buf.len() != self.length_public_key() {
+            None
+        } else {
+            Some(PublicKeyRef::new(buf))
+        }
+    }
+
+    /// Construct a signature object from bytes
+    pub fn signature_from_bytes<'a>(&self, buf: &'a [u8]) -> Option<SignatureRef<'a>> {
+        if buf.len() > self.length_signature() {
+            None
+        } else {
+            Some(SignatureRef::new(buf))
+        }
+    }
+
+    /// Load a secret key that was previously persisted
+    ///
+    /// `buf` is the serialized key as passed to a store callback or
+    /// obtained from [`SecretKey::to_bytes`].
+    /// `store` is called with the new state every time the key is used.
+    pub fn secret_key_from_bytes<F>(&self, buf: &[u8], store: F) -> Result<SecretKey>
+    where
+        F: FnMut(&[u8]) -> Result<()> + Send + 'static,
+    {
+        let mut sk = SecretKey::new(self.algorithm, store)?;
+        let context = sk.store_context();
+        let status = unsafe {
+            ffi::OQS_SIG_STFL_SECRET_KEY_deserialize(
+                sk.sk.as_ptr(),
+                buf.as_ptr(),
+                buf.len(),
+                context,
+            )
+        };
+        status_to_result(status)?;
+        Ok(sk)
+    }
+
+    /// Generate a new keypair
+    ///
+    /// `store` is called with the serialized secret key before this returns,
+    /// and again with the new state every time the key is used to sign.
+    /// If it returns an error, no key or signature is released.
+    pub fn keypair<F>(&self, store: F) -> Result<(PublicKey, SecretKey)>
+    where
+        F: FnMut(&[u8]) -> Result<()> + Send + 'static,
+    {
+        let sig = unsafe { self.sig.as_ref() };
+        let mut sk = SecretKey::new(self.algorithm, store)?;
+        let mut pk = PublicKey {
+            bytes: Vec::with_capacity(sig.length_public_key),
+        };
+        let status = unsafe {
+            ffi::OQS_SIG_STFL_keypair(self.sig.as_ptr(), pk.bytes.as_mut_ptr(), sk.sk.as_ptr())
+        };
+        self.check_status(Operation::Keypair, status)?;
+        // update the length of the vec
+        // this is safe to do, as we have initialised it now.
+        unsafe { pk.bytes.set_len(sig.length_public_key) };
+        sk.persist()?;
+        Ok((pk, sk))
+    }
+
+    /// Sign a message
+    ///
+    /// This updates the secret key and only returns the signature
+    /// after the store callback of `sk` has persisted the new state.
+    pub fn sign(&self, message: &Message, sk: &mut SecretKey) -> Result<Signature> {
+        if sk.algorithm != self.algorithm {
+            return Err(Error::AlgorithmMismatch);
+        }
+        let sig = unsafe { self.sig.as_ref() };
+        let mut signature = Signature {
+            bytes: Vec::with_capacity(sig.length_signature),
+        };
+        let mut sig_len = 0;
+        let status = unsafe {
+            ffi::OQS_SIG_STFL_sign(
+                self.sig.as_ptr(),
+                signature.bytes.as_mut_ptr(),
+                &mut sig_len,
+                message.as_ptr(),
+                message.len(),
+                sk.sk.as_ptr(),
+            )
+        };
+        self.check_status(Operation::Sign, status)?;
+        // This is safe to do as it's initialised now.
+        unsafe { signature.bytes.set_len(sig_len) };
+        Ok(signature)
+    }
+
+    /// Verify a message
+    pub fn verify<'a, 'b>(
+        &self,
+        message: &Message,
+        signature: impl Into<SignatureRef<'a>>,
+        pk: impl Into<PublicKeyRef<'b>>,
+    ) -> Result<()> {
+        let signature = signature.into();
+        let pk = pk.into();
+        if signature.len() > self.length_signature() {
+            return Err(self.wrong_length(
+                Operation::Verify,
+                self.length_signature(),
+                signature.len(),
+            ));
+        }
+        if pk.len() != self.length_public_key() {
+            return Err(self.wrong_length(Operation::Verify, self.length_public_key(), pk.len()));
+        }
+        let status = unsafe {
+            ffi::OQS_SIG_STFL_verify(
+                self.sig.as_ptr(),
+                message.as_ptr(),
+                message.len(),
+                signature.bytes.as_ptr(),
+                signature.len(),
+                pk.bytes.as_ptr(),
+            )
+        };
+        self.check_status(Operation::Verify, status)
+    }
+
+    /// Number of signatures that can still be made with this secret key
+    pub fn sigs_remaining(&self, sk: &SecretKey) -> Result<u64> {
+        if sk.algorithm != self.algorithm {
+            return Err(Error::AlgorithmMismatch);
+        }
+        let mut remaining = 0;
+        let status = unsafe {
+            ffi::OQS_SIG_STFL_sigs_remaining(self.sig.as_ptr(), &mut remaining, sk.sk.as_ptr())
+        };
+        self.check_status(Operation::SigsRemaining, status)?;
+        Ok(remaining)
+    }
+
+    /// Total number of signatures that can be made with this secret key
+    pub fn sigs_total(&self, sk: &SecretKey) -> Result<u64> {
+        if sk.algorithm != self.algorithm {
+            return Err(Error::AlgorithmMismatch);
+        }
+        let mut total = 0;
+        let status =
+            unsafe { ffi::OQS_SIG_STFL_sigs_total(self.sig.as_ptr(), &mut total, sk.sk.as_ptr()) };
+        self.check_status(Operation::SigsTotal, status)?;
+        Ok(total)
+    }
+
+    fn wrong_length(&self, operation: Operation, expected: usize, actual: usize) -> Error {
+        Error::WrongLength {
+            operation,
+            algorithm: self.algorithm.name(),
+            expected,
+            actual,
+        }
+    }
+
+    /// Convert the status returned by liboqs for `operation`
+    fn check_status(&self, operation: Operation, status: OQS_STATUS) -> Result<()> {
+        status_to_result(status)
+            .map_err(|error| error.in_operation(operation, self.algorithm.name()))
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "lms",
+        all(feature = "stateful_key_sig_gen", feature = "std")
+    )
+))]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "lms")]
+    fn test_lms_verify() -> Result<()> {
+        let unhex = |s: &str| {
+            (0..s.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+                .collect::<Vec<u8>>()
+        };
+        let record: Vec<(&str, Vec<u8>)> = include_str!("../testdata/lms.txt")
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(" = "))
+            .map(|(key, value)| (key, unhex(value)))
+            .collect();
+        let get = |key| &record.iter().find(|(k, _)| *k == key).unwrap().1;
+
+        crate::init();
+        let scheme = StatefulSig::new(Algorithm::LmsSha256H5W8)?;
+        let pk = scheme.public_key_from_bytes(get("pk")).unwrap();
+        let signature = scheme.signature_from_bytes(get("sig")).unwrap();
+        let message = get("msg");
+        scheme.verify(message, signature, pk)?;
+
+        let mut tampered = message.clone();
+        tampered[0] ^= 1;
+        assert!(scheme.verify(&tampered, signature, pk).is_err());
+        Ok(())
+    }
+
+    #[cfg(all(feature = "stateful_key_sig_gen", feature = "std"))]
+    mod signing {
+        use super::*;
+        use std::sync::{Arc, Mutex};
+
+        type Stored = Arc<Mutex<Vec<u8>>>;
+
+        /// Store callback that keeps the last persisted state around
+        fn recording_store() -> (Stored, impl FnMut(&[u8]) -> Result<()> + Send + 'static) {
+            let stored = Stored::default();
+            let stored_cb = Arc::clone(&stored);
+            (stored, move |state: &[u8]| {
+                *stored_cb.lock().unwrap() = state.to_vec();
+                Ok(())
+            })
+        }
+
+        fn sign_and_reload(alg: Algorithm) -> Result<()> {
+            crate::init();
+            let message = [0u8; 100];
+            let scheme = StatefulSig::new(alg)?;
+            let (stored, store) = recording_store();
+            let (pk, mut sk) = scheme.keypair(store)?;
+            assert_eq!(stored.lock().unwrap().as_slice(), sk.to_bytes()?.as_ref());
+
+            let total = scheme.sigs_total(&sk)?;
+            assert_eq!(scheme.sigs_remaining(&sk)?, total);
+            let signature = scheme.sign(&message, &mut sk)?;
+            scheme.verify(&message, &signature, &pk)?;
+            assert_eq!(scheme.sigs_remaining(&sk)?, total - 1);
+
+            // the persisted state continues where the key left off
+            let state = stored.lock().unwrap().clone();
+            assert_eq!(state.as_slice(), sk.to_bytes()?.as_ref());
+            let (_, store) = recording_store();
+            let mut reloaded = scheme.secret_key_from_bytes(&state, store)?;
+            assert_eq!(scheme.sigs_remaining(&reloaded)?, total - 1);
+            let signature = scheme.sign(&message, &mut reloaded)?;
+            scheme.verify(&message, &signature, &pk)
+        }
+
+        #[test]
+        #[cfg(feature = "lms")]
+        fn test_lms_signing() -> Result<()> {
+            sign_and_reload(Algorithm::LmsSha256H5W1)
+        }
+
+        #[test]
+        #[cfg(feature = "xmss")]
+        fn test_xmss_signing() -> Result<()> {
+            sign_and_reload(Algorithm::XmssSha256H10)
+        }
+
+        #[test]
+        #[cfg(feature = "lms")]
+        fn test_failed_store_withholds_signature() -> Result<()> {
+            crate::init();
+            let scheme = StatefulSig::new(Algorithm::LmsSha256H5W1)?;
+            let fail = Arc::new(Mutex::new(false));
+            let fail_cb = Arc::clone(&fail);
+            let (_, mut sk) = scheme.keypair(move |_: &[u8]| {
+                if *fail_cb.lock().unwrap() {
+                    Err(Error::Error)
+                } else {
+                    Ok(())
+                }
+            })?;
+            *fail.lock().unwrap() = true;
+            assert!(scheme.sign(&[0u8; 100], &mut sk).is_err());
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(all(feature = "lms", feature = "xmss"))]
+        fn test_algorithm_mismatch() -> Result<()> {
+            crate::init();
+            let lms = StatefulSig::new(Algorithm::LmsSha256H5W1)?;
+            let xmss = StatefulSig::new(Algorithm::XmssSha256H10)?;
+            let (_, mut sk) = lms.keypair(|_: &[u8]| Ok(()))?;
+            assert!(matches!(
+                xmss.sign(&[0u8; 100], &mut sk),
+                Err(Error::AlgorithmMismatch)
+            ));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Shutting down liboqs ends its use for the whole process,
+//! so this runs in its own test binary.
+#![cfg(all(feature = "ml_kem", feature = "std"))]
+
+use oqs::kem::{Algorithm, Kem};
+
+fn encaps_decaps() -> oqs::Result<()> {
+    let kem = Kem::new(Algorithm::MlKem512)?;
+    let (pk, sk) = kem.keypair()?;
+    let (ct, ss) = kem.encapsulate(&pk)?;
+    assert_eq!(kem.decapsulate(&sk, &ct)?, ss);
+    Ok(())
+}
+
+#[test]
+fn test_shutdown() -> oqs::Result<()> {
+    oqs::init();
+    oqs::init();
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| {
+                oqs::thread_stop_on_exit();
+                oqs::thread_stop_on_exit();
+                encaps_decaps()
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap()?;
+    }
+
+    // the thread can keep using liboqs after releasing its state
+    encaps_decaps()?;
+    oqs::thread_stop();
+    encaps_decaps()?;
+    oqs::thread_stop();
+
+    unsafe {
+        oqs::shutdown();
+        oqs::shutdown();
+    }
+    Ok(())
+}